@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::capabilities;
+
+/**
+ * Emitted with an ActionFailure when a user-triggered action fails
+ * Hotkeys and background triggers have no caller to show the error, the UI listens for this instead
+ */
+pub const FAILED_EVENT: &str = "action://failed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionErrorCode {
+    MissingDependency, // an external program isn't installed
+    PermissionDenied,
+    NotFound,
+    Unsupported,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionFailure {
+    pub action: String, // display name, e.g. "Open URL"
+    pub code: ActionErrorCode,
+    pub message: String,
+    pub remediation: Option<String>, // what the user can do about it
+}
+
+impl ActionFailure {
+    pub fn new(action: &str, error: &str) -> Self {
+        let (code, remediation) = classify(error);
+        Self {
+            action: action.to_string(),
+            code,
+            message: error.to_string(),
+            remediation,
+        }
+    }
+}
+
+/**
+ * Pass `result` through, emitting action://failed when it is an error
+ */
+pub fn report<T>(app: &AppHandle, action: &str, result: Result<T, String>) -> Result<T, String> {
+    if let Err(error) = &result {
+        let failure = ActionFailure::new(action, error);
+        log::warn!("Action '{}' failed ({:?}): {}", action, failure.code, error);
+        if let Err(e) = app.emit(FAILED_EVENT, failure) {
+            log::error!("Failed to emit {}: {}", FAILED_EVENT, e);
+        }
+    }
+    result
+}
+
+/**
+ * Error code and remediation for an error message
+ * Errors are plain strings throughout, so this goes by the io error texts they embed
+ */
+fn classify(error: &str) -> (ActionErrorCode, Option<String>) {
+    let lower = error.to_lowercase();
+
+    if error.starts_with(capabilities::MISSING_PREFIX)
+        || lower.contains("os error 2")
+            && (lower.starts_with("failed to run") || lower.starts_with("failed to start"))
+    {
+        let remediation = match missing_tool(error) {
+            Some(tool) => capabilities::install_hint(tool),
+            None => "Install the external tool this action needs and make sure it is on PATH"
+                .to_string(),
+        };
+        return (ActionErrorCode::MissingDependency, Some(remediation));
+    }
+
+    if lower.contains("permission denied")
+        || lower.contains("not permitted")
+        || lower.contains("not allowed")
+        || lower.contains("not authorized")
+        || lower.contains("os error 13")
+    {
+        return (ActionErrorCode::PermissionDenied, Some(permission_hint()));
+    }
+
+    if lower.contains("not supported") {
+        return (ActionErrorCode::Unsupported, None);
+    }
+    if lower.contains("not found") || lower.contains("no existing file") {
+        return (ActionErrorCode::NotFound, None);
+    }
+    (ActionErrorCode::Failed, None)
+}
+
+/**
+ * "Failed to run xdotool: ..." or "Missing dependency: xdotool and ..." -> xdotool
+ */
+fn missing_tool(error: &str) -> Option<&str> {
+    if let Some(rest) = error.strip_prefix(capabilities::MISSING_PREFIX) {
+        return rest.split_whitespace().next();
+    }
+    let rest = error
+        .strip_prefix("Failed to run ")
+        .or_else(|| error.strip_prefix("Failed to start "))?;
+    let tool = rest.split(':').next()?.trim();
+    (!tool.is_empty() && !tool.contains(' ')).then_some(tool)
+}
+
+#[cfg(target_os = "macos")]
+fn permission_hint() -> String {
+    "Allow copyclip under System Settings > Privacy & Security (Accessibility or Screen Recording)"
+        .to_string()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn permission_hint() -> String {
+    "Check that copyclip is allowed to access the file or program".to_string()
+}
@@ -0,0 +1,370 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Prefix of the errors `require` returns, action_feedback reads the tool name after it
+ */
+pub const MISSING_PREFIX: &str = "Missing dependency: ";
+
+/**
+ * Linux display server, decides which external tools a feature uses
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayServer {
+    Wayland,
+    X11,
+    Unknown, // no display, e.g. a tty or ssh session
+}
+
+/**
+ * Feature backed by an external program
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    Screenshot,
+    ActiveWindowScreenshot,
+    ScreenRecording,
+    PrimarySelection,
+    FingerprintUnlock,
+    Webhooks,
+    PasteAndReturn,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 7] = [
+        Feature::Screenshot,
+        Feature::ActiveWindowScreenshot,
+        Feature::ScreenRecording,
+        Feature::PrimarySelection,
+        Feature::FingerprintUnlock,
+        Feature::Webhooks,
+        Feature::PasteAndReturn,
+    ];
+
+    /**
+     * Same as the serialized name
+     */
+    pub fn key(&self) -> &'static str {
+        match self {
+            Feature::Screenshot => "screenshot",
+            Feature::ActiveWindowScreenshot => "active_window_screenshot",
+            Feature::ScreenRecording => "screen_recording",
+            Feature::PrimarySelection => "primary_selection",
+            Feature::FingerprintUnlock => "fingerprint_unlock",
+            Feature::Webhooks => "webhooks",
+            Feature::PasteAndReturn => "paste_and_return",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Feature::Screenshot => "screenshots",
+            Feature::ActiveWindowScreenshot => "context screenshots",
+            Feature::ScreenRecording => "screen recording",
+            Feature::PrimarySelection => "the primary selection",
+            Feature::FingerprintUnlock => "fingerprint unlock",
+            Feature::Webhooks => "webhooks",
+            Feature::PasteAndReturn => "pasting into the previous app",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureSupport {
+    pub feature: Feature,
+    pub supported: bool, // false when this platform has no backend at all
+    pub available: bool,
+    pub backend: Option<String>, // tools that will be used, e.g. "grim + slurp"
+    pub missing: Vec<String>,    // tools of the preferred backend that aren't installed
+    pub remediation: Option<String>,
+}
+
+/**
+ * Result of the startup probe, returned by get_platform_capabilities
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformCapabilities {
+    pub os: String,
+    pub display_server: Option<DisplayServer>, // None outside Linux
+    pub desktop: Option<String>,               // XDG_CURRENT_DESKTOP, e.g. "GNOME"
+    pub features: Vec<FeatureSupport>,
+    pub probed_at: String,
+}
+
+/**
+ * Last probe result; tools installed while the app runs show up after a refresh
+ */
+pub struct Capabilities {
+    last: Mutex<Option<PlatformCapabilities>>,
+}
+
+impl Capabilities {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+
+    /**
+     * Probe again and log every feature that can't work
+     */
+    pub fn refresh(&self) -> PlatformCapabilities {
+        let capabilities = probe();
+        for support in capabilities.features.iter().filter(|s| s.supported) {
+            if !support.available {
+                log::warn!(
+                    "{} unavailable, missing {}: {}",
+                    support.feature.label(),
+                    support.missing.join(", "),
+                    support.remediation.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        *self.last.lock() = Some(capabilities.clone());
+        capabilities
+    }
+
+    pub fn get(&self, refresh: bool) -> PlatformCapabilities {
+        if !refresh {
+            if let Some(capabilities) = self.last.lock().clone() {
+                return capabilities;
+            }
+        }
+        self.refresh()
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn probe() -> PlatformCapabilities {
+    let server = display_server();
+    PlatformCapabilities {
+        os: std::env::consts::OS.to_string(),
+        display_server: server,
+        desktop: std::env::var("XDG_CURRENT_DESKTOP")
+            .ok()
+            .filter(|desktop| !desktop.is_empty()),
+        features: Feature::ALL
+            .iter()
+            .map(|feature| support(*feature, server))
+            .collect(),
+        probed_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+fn support(feature: Feature, server: Option<DisplayServer>) -> FeatureSupport {
+    let Some(backends) = backends(feature, server) else {
+        return FeatureSupport {
+            feature,
+            supported: false,
+            available: false,
+            backend: None,
+            missing: Vec::new(),
+            remediation: Some(format!(
+                "{} is not supported on this platform",
+                capitalize(feature.label())
+            )),
+        };
+    };
+
+    if let Some(found) = backends
+        .iter()
+        .find(|tools| tools.iter().all(|tool| which(tool)))
+    {
+        return FeatureSupport {
+            feature,
+            supported: true,
+            available: true,
+            backend: Some(found.join(" + ")),
+            missing: Vec::new(),
+            remediation: None,
+        };
+    }
+
+    // Nothing usable, point at the first (preferred) backend
+    let missing: Vec<String> = backends
+        .first()
+        .map(|tools| {
+            tools
+                .iter()
+                .filter(|tool| !which(tool))
+                .map(|tool| tool.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut hints: Vec<String> = missing.iter().map(|tool| install_hint(tool)).collect();
+    hints.dedup();
+    FeatureSupport {
+        feature,
+        supported: true,
+        available: false,
+        backend: None,
+        remediation: Some(hints.join("; ")),
+        missing,
+    }
+}
+
+/**
+ * Fail with an actionable message when none of the feature's backends is installed
+ * Unsupported features pass, their backend reports that itself
+ */
+pub fn require(feature: Feature) -> Result<(), String> {
+    let support = support(feature, display_server());
+    if !support.supported || support.available {
+        return Ok(());
+    }
+    Err(format!(
+        "{}{} needed for {}",
+        MISSING_PREFIX,
+        support.missing.join(" and "),
+        feature.label()
+    ))
+}
+
+#[cfg(target_os = "linux")]
+pub fn display_server() -> Option<DisplayServer> {
+    // Same precedence as the backends: a Wayland socket wins even when XWayland sets DISPLAY
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return Some(DisplayServer::Wayland);
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        return Some(DisplayServer::X11);
+    }
+    match std::env::var("XDG_SESSION_TYPE").as_deref() {
+        Ok("wayland") => Some(DisplayServer::Wayland),
+        Ok("x11") => Some(DisplayServer::X11),
+        _ => Some(DisplayServer::Unknown),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn display_server() -> Option<DisplayServer> {
+    None
+}
+
+/**
+ * Alternative tool sets for a feature, in the order its backend tries them
+ * None when the platform has no backend for it
+ */
+#[cfg(target_os = "linux")]
+fn backends(
+    feature: Feature,
+    server: Option<DisplayServer>,
+) -> Option<Vec<&'static [&'static str]>> {
+    let wayland = server == Some(DisplayServer::Wayland);
+    let screenshot: Vec<&'static [&'static str]> = match wayland {
+        true => vec![&["grim", "slurp"], &["gnome-screenshot"], &["import"]],
+        false => vec![&["gnome-screenshot"], &["import"]],
+    };
+
+    Some(match feature {
+        Feature::Screenshot => screenshot,
+        // Wayland can't address the focused window, it captures the full screen instead
+        Feature::ActiveWindowScreenshot if wayland => screenshot,
+        Feature::ActiveWindowScreenshot => {
+            vec![&["xdotool", "import"], &["gnome-screenshot"], &["import"]]
+        }
+        Feature::ScreenRecording if wayland => vec![&["wf-recorder"]],
+        Feature::ScreenRecording => vec![&["ffmpeg"]],
+        Feature::PrimarySelection if wayland => vec![&["wl-copy", "wl-paste"]],
+        Feature::PrimarySelection => vec![&["xclip"]],
+        Feature::FingerprintUnlock => vec![&["fprintd-verify", "fprintd-list"]],
+        Feature::Webhooks => vec![&["curl"]],
+        Feature::PasteAndReturn if wayland => vec![&["wtype"], &["ydotool"]],
+        Feature::PasteAndReturn => vec![&["xdotool"]],
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn backends(
+    feature: Feature,
+    _server: Option<DisplayServer>,
+) -> Option<Vec<&'static [&'static str]>> {
+    match feature {
+        Feature::Screenshot | Feature::ScreenRecording => Some(vec![&["screencapture"]]),
+        Feature::ActiveWindowScreenshot => Some(vec![&["osascript", "screencapture"]]),
+        Feature::PasteAndReturn => Some(vec![&["osascript"]]),
+        Feature::Webhooks => Some(vec![&["curl"]]),
+        Feature::PrimarySelection | Feature::FingerprintUnlock => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn backends(
+    feature: Feature,
+    _server: Option<DisplayServer>,
+) -> Option<Vec<&'static [&'static str]>> {
+    match feature {
+        Feature::Screenshot
+        | Feature::ActiveWindowScreenshot
+        | Feature::FingerprintUnlock
+        | Feature::PasteAndReturn => Some(vec![&["powershell"]]),
+        Feature::ScreenRecording => Some(vec![&["ffmpeg"]]),
+        Feature::Webhooks => Some(vec![&["curl"]]),
+        Feature::PrimarySelection => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn backends(
+    feature: Feature,
+    _server: Option<DisplayServer>,
+) -> Option<Vec<&'static [&'static str]>> {
+    match feature {
+        Feature::Webhooks => Some(vec![&["curl"]]),
+        _ => None,
+    }
+}
+
+/**
+ * Whether `binary` is an executable file on PATH
+ */
+pub fn which(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths).any(|dir| {
+                dir.join(binary).is_file()
+                    || (cfg!(windows) && dir.join(format!("{}.exe", binary)).is_file())
+            })
+        })
+        .unwrap_or(false)
+}
+
+/**
+ * How to get a missing external tool
+ */
+pub fn install_hint(tool: &str) -> String {
+    match tool {
+        "wl-copy" | "wl-paste" => {
+            "Install wl-clipboard (provides wl-copy and wl-paste)".to_string()
+        }
+        "grim" | "slurp" => "Install grim and slurp for Wayland screenshots".to_string(),
+        "import" => "Install ImageMagick (provides import) for X11 screenshots".to_string(),
+        "gnome-screenshot" => "Install gnome-screenshot".to_string(),
+        "xdotool" => "Install xdotool (X11 only)".to_string(),
+        "wtype" | "ydotool" => {
+            "Install wtype, or ydotool with the ydotoold service running (Wayland)".to_string()
+        }
+        "xclip" => "Install xclip for the X11 primary selection".to_string(),
+        "wf-recorder" => "Install wf-recorder for Wayland screen recording".to_string(),
+        "ffmpeg" => "Install ffmpeg and make sure it is on PATH".to_string(),
+        "fprintd-verify" | "fprintd-list" => {
+            "Install fprintd and enroll a finger with fprintd-enroll".to_string()
+        }
+        tool => format!("Install {} and make sure it is on PATH", tool),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
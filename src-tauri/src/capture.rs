@@ -0,0 +1,262 @@
+use tauri::{AppHandle, Manager};
+
+use crate::capture_filter::{CaptureFilterCounters, CaptureFilters, FilterReason};
+use crate::clipboard::ClipboardBackend;
+use crate::context_capture;
+use crate::db::{DatabaseService, SaveOutcome, MAX_ITEMS};
+use crate::dedup::Dedup;
+use crate::files;
+use crate::hooks;
+use crate::image_hash;
+use crate::large_content;
+use crate::metrics::{self, Metric};
+use crate::models::{ClipboardItemModel, SOURCE_CLIPBOARD};
+use crate::paste_stack::PasteStack;
+use crate::url_tracking::{self, TrackingParams};
+use crate::webhook;
+
+/**
+ * Max differing bits between image hashes to treat images as duplicates
+ */
+const IMAGE_DEDUP_THRESHOLD_SETTING: &str = "image_dedup_threshold";
+const DEFAULT_IMAGE_DEDUP_THRESHOLD: u32 = 4;
+
+/**
+ * Where a captured item was copied from
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureSource {
+    Clipboard,
+    PrimarySelection, // X11/Wayland PRIMARY, see primary_selection
+}
+
+/**
+ * Content handed to the save pipeline by a watcher
+ */
+#[derive(Debug, Clone)]
+pub struct CapturedItem {
+    pub id: String,
+    pub content: String,
+    pub item_type: String,
+    pub image_base64: Option<String>,
+    pub file_paths: Option<String>,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+    pub source_url: Option<String>, // page the copy came from (browser extension)
+    pub source_title: Option<String>,
+    pub source: &'static str, // stored SOURCE_*, decides what the item is deduplicated against
+}
+
+impl CapturedItem {
+    pub fn text(content: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            content,
+            item_type: "text".to_string(),
+            image_base64: None,
+            file_paths: None,
+            html: None,
+            rtf: None,
+            source_url: None,
+            source_title: None,
+            source: SOURCE_CLIPBOARD,
+        }
+    }
+}
+
+/**
+ * Image dedup threshold from settings
+ */
+pub fn image_dedup_threshold(db: &DatabaseService) -> u32 {
+    db.get_setting(IMAGE_DEDUP_THRESHOLD_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_IMAGE_DEDUP_THRESHOLD)
+}
+
+/**
+ * Outcome of storing a capture
+ */
+#[derive(Debug)]
+pub enum Stored {
+    Filtered(FilterReason),
+    Duplicate { existing: String, bumped: bool },
+    Saved(Box<ClipboardItemModel>),
+}
+
+/**
+ * Run a capture through filters, hooks, URL cleaning and dedup, then store it
+ * Returns false when the item was filtered out or was a duplicate that wasn't bumped
+ */
+pub fn save(
+    app: &AppHandle,
+    db: &DatabaseService,
+    captured: CapturedItem,
+    source: CaptureSource,
+) -> Result<bool, String> {
+    let item = match store(db, captured)? {
+        Stored::Filtered(reason) => {
+            app.state::<CaptureFilterCounters>().record(reason);
+            return Ok(false);
+        }
+        Stored::Duplicate { existing, bumped } => {
+            // Copying the same text twice while stacking queues it twice on purpose,
+            // the stack replays the copies in the order they were made
+            if app.state::<PasteStack>().capture(&existing) {
+                log::debug!("[SAVE] Existing item queued on paste stack");
+            }
+            return Ok(bumped);
+        }
+        Stored::Saved(item) => item,
+    };
+
+    if app.state::<PasteStack>().capture(&item.id) {
+        log::debug!("[SAVE] Item queued on paste stack");
+    }
+
+    // Only the clipboard is rewritten, a selection is left as the user made it
+    if source == CaptureSource::Clipboard
+        && item.original_content.is_some()
+        && url_tracking::replace_clipboard(db)
+    {
+        // The cleaned copy is then captured again as a duplicate of this item
+        if let Some(backend) = app.try_state::<ClipboardBackend>() {
+            if let Err(e) = backend.provider().write_text(item.content.clone()) {
+                log::warn!("[SAVE] Failed to replace clipboard with cleaned URL: {}", e);
+            }
+        }
+    }
+
+    webhook::notify(app, &item);
+    context_capture::capture_for(app, &item.id);
+
+    log::debug!("[SAVE] Item saved successfully");
+
+    Ok(true) // Item saved successfully
+}
+
+/**
+ * The database half of save, usable without the app (native messaging host)
+ * Side effects that need the running app (paste stack, webhook, context) are left to the caller
+ */
+pub fn store(db: &DatabaseService, captured: CapturedItem) -> Result<Stored, String> {
+    let CapturedItem {
+        id,
+        content,
+        item_type,
+        image_base64,
+        file_paths,
+        html,
+        rtf,
+        source_url,
+        source_title,
+        source,
+    } = captured;
+    log::debug!(
+        "[SAVE] Attempting to save item with id: {}, type: {}, content length: {}",
+        id,
+        item_type,
+        content.len()
+    );
+
+    // Capture guards configured in settings
+    let filters = CaptureFilters::from_settings(db);
+    if let Some(reason) = filters.check(&content, &item_type, image_base64.as_deref()) {
+        log::debug!("[SAVE] Item skipped by capture filter: {:?}", reason);
+        return Ok(Stored::Filtered(reason));
+    }
+
+    // on_item_saved hooks may rewrite text before it is deduplicated and stored
+    let is_text = image_base64.is_none() && item_type != "file";
+    let content = if is_text {
+        hooks::run(&hooks::load(db), content)
+    } else {
+        content
+    };
+
+    // Tracking parameters are stripped from URLs, the text as copied is kept alongside
+    let (content, original_content) = if is_text && url_tracking::enabled(db) {
+        let cleaned = url_tracking::strip(&content, &TrackingParams::from_settings(db));
+        if cleaned != content {
+            (cleaned, Some(content))
+        } else {
+            (content, None)
+        }
+    } else {
+        (content, None)
+    };
+
+    // Very large text goes to a file, the database keeps a preview
+    let external = if is_text {
+        large_content::externalize(db, &content)?
+    } else {
+        None
+    };
+    let content = match &external {
+        Some(external) => external.preview.clone(),
+        None => content,
+    };
+    let content_file = external.as_ref().map(|external| external.file.clone());
+
+    // Images share placeholder content, dedup them by perceptual hash instead
+    let image_hash = image_base64.as_deref().and_then(image_hash::dhash);
+
+    let mut item = ClipboardItemModel::new(
+        id.clone(),
+        content,
+        item_type,
+        image_base64,
+        file_paths.clone(),
+    );
+    item.html = html;
+    item.rtf = rtf;
+    item.source_url = source_url;
+    item.source_title = source_title;
+    item.source = source.to_string();
+    item.image_hash = image_hash;
+    item.content_file = content_file;
+    // Large originals aren't duplicated into the database
+    item.original_content = original_content.filter(|_| external.is_none());
+    item.content_size = external.map(|external| external.size);
+
+    // Duplicate check, insert and max items limit run in one transaction
+    log::debug!("[SAVE] Saving item: {:?}", id);
+    let dedup = Dedup::from_settings(db);
+    let threshold = image_dedup_threshold(db);
+    let evicted = match db.save_item(&item, &dedup, threshold, MAX_ITEMS) {
+        Ok(SaveOutcome::Saved { evicted }) => evicted,
+        Ok(SaveOutcome::Duplicate { existing, bumped }) => {
+            // Bump policy moves the existing item to the top instead
+            log::debug!(
+                "[SAVE] Item is duplicate of {}, skipping save (bumped: {})",
+                existing,
+                bumped
+            );
+            return Ok(Stored::Duplicate { existing, bumped });
+        }
+        Err(e) => {
+            log::error!("[SAVE] Failed to save item: {}", e);
+            return Err(format!("Failed to save item: {}", e));
+        }
+    };
+    if evicted > 0 {
+        log::debug!("[SAVE] Enforced max items, deleted {} old items", evicted);
+        if let Err(e) = db.record_audit("evict", Some("max items limit"), evicted) {
+            log::error!("Failed to record audit entry 'evict': {}", e);
+        }
+    }
+    metrics::record(db, Metric::Capture, None);
+
+    if let Some(paths) = file_paths
+        .as_deref()
+        .and_then(|p| files::parse_paths(p).ok())
+    {
+        let file_rows: Vec<_> = paths.iter().map(|path| files::inspect(&id, path)).collect();
+        if let Err(e) = db.save_item_files(&id, &file_rows) {
+            log::error!("[SAVE] Failed to save file metadata: {}", e);
+        }
+    }
+
+    Ok(Stored::Saved(Box::new(item)))
+}
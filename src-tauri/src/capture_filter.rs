@@ -0,0 +1,186 @@
+use base64::Engine;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::db::DatabaseService;
+
+pub const MIN_CHARS_SETTING: &str = "capture_min_chars";
+pub const MAX_BYTES_SETTING: &str = "capture_max_bytes";
+pub const SKIP_BINARY_SETTING: &str = "capture_skip_binary";
+pub const MAX_IMAGE_PIXELS_SETTING: &str = "capture_max_image_pixels";
+
+/**
+ * Share of control/replacement characters above which text counts as binary
+ */
+const BINARY_RATIO: f64 = 0.1;
+
+/**
+ * Why a copy was not saved to history
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    TooShort,
+    TooLarge,
+    Binary,
+    ImageTooLarge,
+}
+
+/**
+ * Number of copies skipped by each capture filter since launch
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureFilterStats {
+    pub too_short: u64,
+    pub too_large: u64,
+    pub binary: u64,
+    pub image_too_large: u64,
+}
+
+/**
+ * Capture guards read from settings; 0 / false disables a guard
+ */
+pub struct CaptureFilters {
+    pub min_chars: usize,
+    pub max_bytes: usize,
+    pub skip_binary: bool,
+    pub max_image_pixels: u64,
+}
+
+impl CaptureFilters {
+    pub fn from_settings(db: &DatabaseService) -> Self {
+        let number = |key: &str| {
+            db.get_setting(key)
+                .ok()
+                .flatten()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+
+        Self {
+            min_chars: number(MIN_CHARS_SETTING) as usize,
+            max_bytes: number(MAX_BYTES_SETTING) as usize,
+            skip_binary: matches!(
+                db.get_setting(SKIP_BINARY_SETTING)
+                    .ok()
+                    .flatten()
+                    .as_deref(),
+                Some("true")
+            ),
+            max_image_pixels: number(MAX_IMAGE_PIXELS_SETTING),
+        }
+    }
+
+    /**
+     * Return the first guard the copy trips, if any
+     */
+    pub fn check(
+        &self,
+        content: &str,
+        item_type: &str,
+        image_base64: Option<&str>,
+    ) -> Option<FilterReason> {
+        let is_text = image_base64.is_none() && item_type != "file";
+
+        if is_text && self.min_chars > 0 && content.trim().chars().count() < self.min_chars {
+            return Some(FilterReason::TooShort);
+        }
+
+        let size = content.len() + image_base64.map_or(0, str::len);
+        if self.max_bytes > 0 && size > self.max_bytes {
+            return Some(FilterReason::TooLarge);
+        }
+
+        if is_text && self.skip_binary && looks_binary(content) {
+            return Some(FilterReason::Binary);
+        }
+
+        if self.max_image_pixels > 0 {
+            if let Some((width, height)) = image_base64.and_then(image_dimensions) {
+                if u64::from(width) * u64::from(height) > self.max_image_pixels {
+                    return Some(FilterReason::ImageTooLarge);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/**
+ * Filter counters kept in app state
+ */
+pub struct CaptureFilterCounters {
+    stats: Mutex<CaptureFilterStats>,
+}
+
+impl CaptureFilterCounters {
+    pub fn new() -> Self {
+        Self {
+            stats: Mutex::new(CaptureFilterStats::default()),
+        }
+    }
+
+    pub fn record(&self, reason: FilterReason) {
+        let mut stats = self.stats.lock();
+        match reason {
+            FilterReason::TooShort => stats.too_short += 1,
+            FilterReason::TooLarge => stats.too_large += 1,
+            FilterReason::Binary => stats.binary += 1,
+            FilterReason::ImageTooLarge => stats.image_too_large += 1,
+        }
+    }
+
+    pub fn snapshot(&self) -> CaptureFilterStats {
+        self.stats.lock().clone()
+    }
+
+    pub fn reset(&self) -> CaptureFilterStats {
+        std::mem::take(&mut *self.stats.lock())
+    }
+}
+
+impl Default for CaptureFilterCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Text containing NULs or mostly control/replacement characters is treated as binary
+ */
+fn looks_binary(text: &str) -> bool {
+    let mut total = 0usize;
+    let mut suspicious = 0usize;
+
+    for c in text.chars() {
+        if c == '\0' {
+            return true;
+        }
+        total += 1;
+        if c == '\u{FFFD}' || (c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+            suspicious += 1;
+        }
+    }
+
+    total > 0 && suspicious as f64 / total as f64 > BINARY_RATIO
+}
+
+/**
+ * Read image width/height from the header without decoding pixels
+ */
+fn image_dimensions(image_base64: &str) -> Option<(u32, u32)> {
+    let data = image_base64
+        .split_once("base64,")
+        .map(|(_, data)| data)
+        .unwrap_or(image_base64);
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .ok()?;
+
+    image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
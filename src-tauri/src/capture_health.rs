@@ -0,0 +1,230 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clipboard::{ClipboardBackend, ClipboardProvider};
+use crate::shutdown::Shutdown;
+
+/**
+ * Emitted with a CaptureHealthModel when the clipboard changed but nothing was captured
+ */
+pub const STALLED_EVENT: &str = "capture://stalled";
+/**
+ * Emitted with a CaptureHealthModel when captures arrive again after a stall
+ */
+pub const RECOVERED_EVENT: &str = "capture://recovered";
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+/**
+ * How long a clipboard change may go uncaptured before the watcher counts as stalled
+ */
+const STALL_AFTER: Duration = Duration::from_secs(15);
+/**
+ * Minimum time between automatic watcher restarts
+ */
+const RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureStatus {
+    Unknown, // nothing captured yet this session, the watchdog isn't armed
+    Healthy,
+    Stalled,
+}
+
+/**
+ * Snapshot returned by get_capture_health and sent with the events
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureHealthModel {
+    pub status: CaptureStatus,
+    pub last_capture_at: Option<i64>,  // ms timestamp
+    pub last_probe_at: Option<i64>,    // ms timestamp
+    pub uncaptured_since: Option<i64>, // ms timestamp of a change no capture followed yet
+    pub monitor_running: bool,
+    pub restarts: u32,
+}
+
+struct HealthState {
+    last_capture: Option<(Instant, i64)>,
+    last_probe_at: Option<i64>,
+    fingerprint: Option<u64>,
+    uncaptured: Option<(Instant, i64)>,
+    monitor_running: bool,
+    stalled: bool,
+    last_restart: Option<Instant>,
+    restarts: u32,
+}
+
+/**
+ * Watchdog for the clipboard watcher
+ * A probe thread fingerprints the clipboard; a change that no capture follows
+ * within STALL_AFTER means the watcher died, so it is reported and restarted
+ */
+pub struct CaptureHealth {
+    state: Mutex<HealthState>,
+}
+
+impl CaptureHealth {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(HealthState {
+                last_capture: None,
+                last_probe_at: None,
+                fingerprint: None,
+                uncaptured: None,
+                monitor_running: false,
+                stalled: false,
+                last_restart: None,
+                restarts: 0,
+            }),
+        }
+    }
+
+    /**
+     * Note that the watcher delivered a clipboard update
+     */
+    pub fn record_capture(&self, app: &AppHandle) {
+        let recovered = {
+            let mut state = self.state.lock();
+            state.last_capture = Some((Instant::now(), chrono::Utc::now().timestamp_millis()));
+            state.uncaptured = None;
+            std::mem::replace(&mut state.stalled, false)
+        };
+
+        if recovered {
+            log::info!("Clipboard capture recovered");
+            emit(app, RECOVERED_EVENT, self.snapshot());
+        }
+    }
+
+    pub fn snapshot(&self) -> CaptureHealthModel {
+        let state = self.state.lock();
+        let status = if state.stalled {
+            CaptureStatus::Stalled
+        } else if state.last_capture.is_some() {
+            CaptureStatus::Healthy
+        } else {
+            CaptureStatus::Unknown
+        };
+
+        CaptureHealthModel {
+            status,
+            last_capture_at: state.last_capture.map(|(_, at)| at),
+            last_probe_at: state.last_probe_at,
+            uncaptured_since: state.uncaptured.map(|(_, at)| at),
+            monitor_running: state.monitor_running,
+            restarts: state.restarts,
+        }
+    }
+
+    /**
+     * Record one probe, returning whether the watcher should be restarted
+     */
+    fn probe(&self, fingerprint: Option<u64>, monitor_running: bool) -> bool {
+        let mut state = self.state.lock();
+        let now = chrono::Utc::now().timestamp_millis();
+        state.last_probe_at = Some(now);
+        state.monitor_running = monitor_running;
+
+        // Until something is captured the monitor may simply not be started yet
+        let Some((captured, _)) = state.last_capture else {
+            state.fingerprint = fingerprint;
+            return false;
+        };
+
+        if fingerprint.is_some() && fingerprint != state.fingerprint {
+            // A capture within the last probe interval already covered this change
+            if captured.elapsed() > PROBE_INTERVAL && state.uncaptured.is_none() {
+                state.uncaptured = Some((Instant::now(), now));
+            }
+            state.fingerprint = fingerprint;
+        }
+
+        let missed = state
+            .uncaptured
+            .is_some_and(|(since, _)| since.elapsed() >= STALL_AFTER);
+        if !missed && monitor_running {
+            return false;
+        }
+
+        state.stalled = true;
+        if state
+            .last_restart
+            .is_some_and(|restarted| restarted.elapsed() < RESTART_BACKOFF)
+        {
+            return false;
+        }
+        state.last_restart = Some(Instant::now());
+        state.restarts += 1;
+        // Judge the restarted watcher on the next change only
+        state.uncaptured = None;
+        true
+    }
+}
+
+impl Default for CaptureHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Start the probe thread
+ */
+pub fn start(app: AppHandle) {
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("capture-health", move |token| {
+        while token.sleep(PROBE_INTERVAL) {
+            let (Some(health), Some(backend)) = (
+                app.try_state::<CaptureHealth>(),
+                app.try_state::<ClipboardBackend>(),
+            ) else {
+                continue;
+            };
+            let clipboard = backend.provider();
+
+            if !health.probe(fingerprint(clipboard), clipboard.is_watching()) {
+                continue;
+            }
+
+            let snapshot = health.snapshot();
+            log::warn!(
+                "Clipboard capture appears stalled (last capture {:?}), restarting watcher",
+                snapshot.last_capture_at
+            );
+            emit(&app, STALLED_EVENT, snapshot);
+
+            if let Err(e) = clipboard.restart_watcher() {
+                log::error!("Failed to restart clipboard watcher: {}", e);
+            }
+        }
+    });
+}
+
+/**
+ * Hash of the clipboard's text or file list, None when it holds neither
+ * Images are left out: reading one means encoding it to PNG every probe. A watcher that
+ * died while only images were copied is still caught on the next text or file copy.
+ */
+fn fingerprint(clipboard: &dyn ClipboardProvider) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    match clipboard.read_text() {
+        Ok(text) if !text.trim().is_empty() => text.hash(&mut hasher),
+        _ => match clipboard.read_files() {
+            Ok(files) if !files.is_empty() => files.hash(&mut hasher),
+            _ => return None,
+        },
+    }
+    Some(hasher.finish())
+}
+
+fn emit(app: &AppHandle, event: &str, health: CaptureHealthModel) {
+    if let Err(e) = app.emit(event, health) {
+        log::error!("Failed to emit {}: {}", event, e);
+    }
+}
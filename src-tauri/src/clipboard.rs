@@ -0,0 +1,450 @@
+use clipboard_rs::{Clipboard as _, ClipboardContent, ClipboardContext};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard::Clipboard;
+
+use crate::db::DatabaseService;
+use crate::large_content;
+use crate::models::ClipboardItemModel;
+use crate::paste_format::{self, PasteFormat};
+
+/**
+ * Set to "memory" to use MemoryClipboard instead of the OS clipboard, e.g. for headless test runs
+ */
+pub const PROVIDER_ENV: &str = "COPYCLIP_CLIPBOARD";
+
+/**
+ * Clipboard access used by capture checks and write-back
+ */
+pub trait ClipboardProvider: Send + Sync {
+    fn read_text(&self) -> Result<String, String>;
+    fn read_image_base64(&self) -> Result<String, String>;
+    fn read_files(&self) -> Result<Vec<String>, String>;
+
+    fn write_text(&self, text: String) -> Result<(), String>;
+    fn write_html_and_text(&self, html: String, text: String) -> Result<(), String>;
+    fn write_rtf_and_text(&self, rtf: String, text: String) -> Result<(), String>;
+    fn write_image_base64(&self, image: String) -> Result<(), String>;
+    fn write_files(&self, files: Vec<String>) -> Result<(), String>;
+
+    /**
+     * Whether clipboard changes are being delivered to the capture pipeline
+     */
+    fn is_watching(&self) -> bool;
+    fn restart_watcher(&self) -> Result<(), String>;
+}
+
+/**
+ * The OS clipboard through the clipboard plugin
+ */
+pub struct SystemClipboard {
+    app: AppHandle,
+}
+
+impl SystemClipboard {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    fn plugin(&self) -> tauri::State<'_, Clipboard> {
+        self.app.state::<Clipboard>()
+    }
+}
+
+impl ClipboardProvider for SystemClipboard {
+    fn read_text(&self) -> Result<String, String> {
+        self.plugin().read_text()
+    }
+
+    fn read_image_base64(&self) -> Result<String, String> {
+        self.plugin().read_image_base64()
+    }
+
+    fn read_files(&self) -> Result<Vec<String>, String> {
+        self.plugin().read_files()
+    }
+
+    fn write_text(&self, text: String) -> Result<(), String> {
+        self.plugin().write_text(text)
+    }
+
+    fn write_html_and_text(&self, html: String, text: String) -> Result<(), String> {
+        self.plugin().write_html_and_text(html, text)
+    }
+
+    fn write_rtf_and_text(&self, rtf: String, text: String) -> Result<(), String> {
+        // The plugin writes RTF as the only flavor and every write clears the clipboard,
+        // so both go in one write for apps that only paste plain text
+        let context = ClipboardContext::new().map_err(|e| e.to_string())?;
+        context
+            .set(vec![
+                ClipboardContent::Rtf(rtf),
+                ClipboardContent::Text(text),
+            ])
+            .map_err(|e| e.to_string())
+    }
+
+    fn write_image_base64(&self, image: String) -> Result<(), String> {
+        self.plugin().write_image_base64(image)
+    }
+
+    fn write_files(&self, files: Vec<String>) -> Result<(), String> {
+        self.plugin().write_files_uris(files)
+    }
+
+    fn is_watching(&self) -> bool {
+        self.plugin().is_monitor_running()
+    }
+
+    fn restart_watcher(&self) -> Result<(), String> {
+        let clipboard = self.plugin();
+        if clipboard.is_monitor_running() {
+            clipboard.stop_monitor(self.app.clone())?;
+        }
+        clipboard.start_monitor(self.app.clone())
+    }
+}
+
+/**
+ * What a MemoryClipboard currently holds
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MemoryContents {
+    #[default]
+    Empty,
+    Text {
+        text: String,
+        html: Option<String>,
+        rtf: Option<String>,
+    },
+    Image(String), // base64 PNG
+    Files(Vec<String>),
+}
+
+/**
+ * In-memory clipboard, holds the last write and reads it back
+ */
+#[derive(Default)]
+pub struct MemoryClipboard {
+    contents: Mutex<MemoryContents>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn set(&self, contents: MemoryContents) -> Result<(), String> {
+        *self.contents.lock() = contents;
+        Ok(())
+    }
+}
+
+impl ClipboardProvider for MemoryClipboard {
+    fn read_text(&self) -> Result<String, String> {
+        match &*self.contents.lock() {
+            MemoryContents::Text { text, .. } => Ok(text.clone()),
+            _ => Err("Clipboard has no text".to_string()),
+        }
+    }
+
+    fn read_image_base64(&self) -> Result<String, String> {
+        match &*self.contents.lock() {
+            MemoryContents::Image(image) => Ok(image.clone()),
+            _ => Err("Clipboard has no image".to_string()),
+        }
+    }
+
+    fn read_files(&self) -> Result<Vec<String>, String> {
+        match &*self.contents.lock() {
+            MemoryContents::Files(files) => Ok(files.clone()),
+            _ => Err("Clipboard has no files".to_string()),
+        }
+    }
+
+    fn write_text(&self, text: String) -> Result<(), String> {
+        self.set(MemoryContents::Text {
+            text,
+            html: None,
+            rtf: None,
+        })
+    }
+
+    fn write_html_and_text(&self, html: String, text: String) -> Result<(), String> {
+        self.set(MemoryContents::Text {
+            text,
+            html: Some(html),
+            rtf: None,
+        })
+    }
+
+    fn write_rtf_and_text(&self, rtf: String, text: String) -> Result<(), String> {
+        self.set(MemoryContents::Text {
+            text,
+            html: None,
+            rtf: Some(rtf),
+        })
+    }
+
+    fn write_image_base64(&self, image: String) -> Result<(), String> {
+        self.set(MemoryContents::Image(image))
+    }
+
+    fn write_files(&self, files: Vec<String>) -> Result<(), String> {
+        self.set(MemoryContents::Files(files))
+    }
+
+    fn is_watching(&self) -> bool {
+        true
+    }
+
+    fn restart_watcher(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/**
+ * Managed clipboard provider
+ */
+pub struct ClipboardBackend {
+    provider: Box<dyn ClipboardProvider>,
+}
+
+impl ClipboardBackend {
+    pub fn new(provider: impl ClipboardProvider + 'static) -> Self {
+        Self {
+            provider: Box::new(provider),
+        }
+    }
+
+    /**
+     * The OS clipboard, or MemoryClipboard when COPYCLIP_CLIPBOARD=memory
+     */
+    pub fn from_env(app: AppHandle) -> Self {
+        match std::env::var(PROVIDER_ENV).as_deref() {
+            Ok("memory") => {
+                log::info!("Using the in-memory clipboard ({}=memory)", PROVIDER_ENV);
+                Self::new(MemoryClipboard::new())
+            }
+            _ => Self::new(SystemClipboard::new(app)),
+        }
+    }
+
+    pub fn provider(&self) -> &dyn ClipboardProvider {
+        self.provider.as_ref()
+    }
+}
+
+/**
+ * Write a history item back to the clipboard
+ * With `rich` set, HTML/RTF flavors are restored alongside the plain text
+ */
+pub fn write_item(
+    clipboard: &dyn ClipboardProvider,
+    db: &DatabaseService,
+    item: &ClipboardItemModel,
+    rich: bool,
+) -> Result<(), String> {
+    let text = match &item.content_file {
+        Some(_) => large_content::full_content(db, item)?,
+        None => item.content.clone(),
+    };
+
+    match item.item_type.as_str() {
+        "image_base64" | "image" => {
+            let image = item
+                .image_base64
+                .clone()
+                .ok_or_else(|| "Image item has no image data".to_string())?;
+            clipboard.write_image_base64(image)
+        }
+        "file" => {
+            let files: Vec<String> = item
+                .file_paths
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()
+                .map_err(|e| format!("Invalid file list: {}", e))?
+                .unwrap_or_default();
+            clipboard.write_files(files)
+        }
+        _ if rich => {
+            // The HTML watcher stores markup as content when no html flavor is set
+            let html = item
+                .html
+                .clone()
+                .or_else(|| (item.item_type == "html").then(|| text.clone()));
+
+            if let Some(html) = html {
+                clipboard.write_html_and_text(html, text)
+            } else if let Some(rtf) = item.rtf.clone() {
+                clipboard.write_rtf_and_text(rtf, text)
+            } else {
+                clipboard.write_text(text)
+            }
+        }
+        _ => clipboard.write_text(text),
+    }
+}
+
+/**
+ * Write an item converted to another format (see paste_format)
+ */
+pub fn write_converted(
+    clipboard: &dyn ClipboardProvider,
+    db: &DatabaseService,
+    item: &ClipboardItemModel,
+    format: PasteFormat,
+) -> Result<(), String> {
+    let text = large_content::full_content(db, item)?;
+    let converted = paste_format::convert(db, item, text, format)?;
+
+    match converted.html {
+        Some(html) => clipboard.write_html_and_text(html, converted.text),
+        None => clipboard.write_text(converted.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{SaveOutcome, MAX_ITEMS};
+    use crate::dedup::Dedup;
+
+    fn temp_db() -> (DatabaseService, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("copyclip-test-{}.db", uuid::Uuid::new_v4()));
+        (DatabaseService::new(path.clone()).unwrap(), path)
+    }
+
+    /**
+     * Read what the user copied the way the watcher does and store it
+     */
+    fn capture(clipboard: &MemoryClipboard, db: &DatabaseService) -> (String, SaveOutcome) {
+        let text = clipboard.read_text().unwrap();
+        let mut item = ClipboardItemModel::new(
+            uuid::Uuid::new_v4().to_string(),
+            text,
+            "text".to_string(),
+            None,
+            None,
+        );
+        if let MemoryContents::Text { html, rtf, .. } = &*clipboard.contents.lock() {
+            item.html = html.clone();
+            item.rtf = rtf.clone();
+        }
+        let outcome = db
+            .save_item(&item, &Dedup::from_settings(db), 4, MAX_ITEMS)
+            .unwrap();
+        (item.id, outcome)
+    }
+
+    #[test]
+    fn capture_save_paste_round_trip() {
+        let (db, path) = temp_db();
+        let clipboard = MemoryClipboard::new();
+
+        clipboard
+            .write_html_and_text("<b>hello</b>".to_string(), "hello".to_string())
+            .unwrap();
+        let (id, outcome) = capture(&clipboard, &db);
+        assert!(matches!(outcome, SaveOutcome::Saved { .. }));
+
+        // Copying the same text again is a duplicate, not a second item
+        let (_, outcome) = capture(&clipboard, &db);
+        assert!(matches!(outcome, SaveOutcome::Duplicate { existing, .. } if existing == id));
+        assert_eq!(db.count_items().unwrap(), 1);
+
+        clipboard.write_text("something else".to_string()).unwrap();
+        let item = db.get_item(&id).unwrap().unwrap();
+        assert_eq!(item.content, "hello");
+
+        // Plain paste drops the markup, rich paste restores it
+        write_item(&clipboard, &db, &item, false).unwrap();
+        assert_eq!(
+            clipboard.contents.lock().clone(),
+            MemoryContents::Text {
+                text: "hello".to_string(),
+                html: None,
+                rtf: None,
+            }
+        );
+        write_item(&clipboard, &db, &item, true).unwrap();
+        assert_eq!(
+            clipboard.contents.lock().clone(),
+            MemoryContents::Text {
+                text: "hello".to_string(),
+                html: Some("<b>hello</b>".to_string()),
+                rtf: None,
+            }
+        );
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn rtf_paste_keeps_plain_text() {
+        let (db, path) = temp_db();
+        let clipboard = MemoryClipboard::new();
+
+        let rtf = r"{\rtf1 {\b bold}}".to_string();
+        clipboard
+            .write_rtf_and_text(rtf.clone(), "bold".to_string())
+            .unwrap();
+        let (id, _) = capture(&clipboard, &db);
+
+        clipboard.write_text("something else".to_string()).unwrap();
+        let item = db.get_item(&id).unwrap().unwrap();
+        write_item(&clipboard, &db, &item, true).unwrap();
+        assert_eq!(
+            clipboard.contents.lock().clone(),
+            MemoryContents::Text {
+                text: "bold".to_string(),
+                html: None,
+                rtf: Some(rtf),
+            }
+        );
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn image_and_files_round_trip() {
+        let (db, path) = temp_db();
+        let clipboard = MemoryClipboard::new();
+
+        let mut image = ClipboardItemModel::new(
+            uuid::Uuid::new_v4().to_string(),
+            "[Image]".to_string(),
+            "image_base64".to_string(),
+            Some("aGVsbG8=".to_string()),
+            None,
+        );
+        image.image_hash = Some("image".to_string());
+        db.save_item(&image, &Dedup::from_settings(&db), 4, MAX_ITEMS)
+            .unwrap();
+        let stored = db.get_item(&image.id).unwrap().unwrap();
+        write_item(&clipboard, &db, &stored, true).unwrap();
+        assert_eq!(clipboard.read_image_base64().unwrap(), "aGVsbG8=");
+
+        let files = ClipboardItemModel::new(
+            uuid::Uuid::new_v4().to_string(),
+            "a.txt".to_string(),
+            "file".to_string(),
+            None,
+            Some(r#"["/tmp/a.txt","/tmp/b.txt"]"#.to_string()),
+        );
+        db.save_item(&files, &Dedup::from_settings(&db), 4, MAX_ITEMS)
+            .unwrap();
+        let stored = db.get_item(&files.id).unwrap().unwrap();
+        write_item(&clipboard, &db, &stored, false).unwrap();
+        assert_eq!(
+            clipboard.read_files().unwrap(),
+            vec!["/tmp/a.txt".to_string(), "/tmp/b.txt".to_string()]
+        );
+
+        drop(db);
+        let _ = std::fs::remove_file(path);
+    }
+}
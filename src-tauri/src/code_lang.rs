@@ -0,0 +1,299 @@
+/**
+ * Per-language marker strings; each marker found in a snippet adds one point
+ */
+const MARKERS: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "fn ",
+            "let mut ",
+            "impl ",
+            "pub fn",
+            "::new(",
+            "&self",
+            "&str",
+            "-> Result<",
+            "println!",
+            "#[derive",
+            "use std::",
+            "match ",
+            "Some(",
+            "Ok(",
+            ".unwrap()",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "def ",
+            "import ",
+            "from ",
+            "self.",
+            "elif ",
+            "None",
+            "True",
+            "False",
+            "print(",
+            "__init__",
+            "    return ",
+            "lambda ",
+            " in range(",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            "const ",
+            "let ",
+            "function ",
+            "=> ",
+            "console.log",
+            "require(",
+            "module.exports",
+            "document.",
+            "===",
+            "async ",
+            "await ",
+            "export default",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            "interface ",
+            ": string",
+            ": number",
+            ": boolean",
+            "export type ",
+            "<T>",
+            "readonly ",
+            "import type ",
+            "as const",
+            "implements ",
+        ],
+    ),
+    (
+        "go",
+        &[
+            "package ",
+            "func ",
+            ":= ",
+            "fmt.",
+            "err != nil",
+            "go func",
+            "chan ",
+            "defer ",
+            "[]string",
+            "struct {",
+        ],
+    ),
+    (
+        "java",
+        &[
+            "public class ",
+            "public static void",
+            "System.out",
+            "private ",
+            "import java.",
+            "@Override",
+            "new ",
+            "extends ",
+            "String[]",
+        ],
+    ),
+    (
+        "c",
+        &[
+            "#include <",
+            "int main(",
+            "printf(",
+            "malloc(",
+            "sizeof(",
+            "->",
+            "NULL",
+            "char *",
+            "#define ",
+        ],
+    ),
+    (
+        "cpp",
+        &[
+            "#include <",
+            "std::",
+            "cout <<",
+            "template<",
+            "template <",
+            "nullptr",
+            "namespace ",
+            "::iterator",
+        ],
+    ),
+    (
+        "csharp",
+        &[
+            "using System",
+            "namespace ",
+            "public class ",
+            "Console.Write",
+            "var ",
+            "{ get; set; }",
+            "async Task",
+            "string[]",
+        ],
+    ),
+    (
+        "ruby",
+        &[
+            "def ",
+            "end\n",
+            "puts ",
+            "require '",
+            "attr_accessor",
+            ".each do",
+            "do |",
+            "nil",
+        ],
+    ),
+    (
+        "php",
+        &[
+            "<?php",
+            "$this->",
+            "function ",
+            "echo ",
+            "=> $",
+            "->",
+            "public function",
+            "array(",
+        ],
+    ),
+    (
+        "shell",
+        &[
+            "#!/bin/", "echo ", "fi\n", "then\n", "done\n", "export ", "$(", "sudo ", " | grep",
+            "&& ",
+        ],
+    ),
+    (
+        "sql",
+        &[
+            "SELECT ",
+            "FROM ",
+            "WHERE ",
+            "INSERT INTO",
+            "UPDATE ",
+            "JOIN ",
+            "CREATE TABLE",
+            "GROUP BY",
+            "ORDER BY",
+        ],
+    ),
+    (
+        "html",
+        &[
+            "<!DOCTYPE",
+            "<html",
+            "<div",
+            "</div>",
+            "<span",
+            "<a href",
+            "<body",
+            "class=\"",
+        ],
+    ),
+    (
+        "css",
+        &[
+            "{\n", "px;", "color:", "margin:", "padding:", "display:", "@media", "font-",
+        ],
+    ),
+    (
+        "swift",
+        &[
+            "func ",
+            "let ",
+            "var ",
+            "guard ",
+            "import UIKit",
+            "import SwiftUI",
+            "-> ",
+            "?? ",
+        ],
+    ),
+    (
+        "kotlin",
+        &[
+            "fun ",
+            "val ",
+            "var ",
+            "data class",
+            "println(",
+            "companion object",
+            "?.let",
+        ],
+    ),
+];
+
+/**
+ * Points a language needs before a snippet is tagged with it
+ */
+const MIN_SCORE: usize = 2;
+
+/**
+ * Guess the programming language of a text snippet, None for prose
+ */
+pub fn detect(text: &str) -> Option<&'static str> {
+    let trimmed = text.trim();
+    if trimmed.starts_with("#!") {
+        let shebang = trimmed.lines().next().unwrap_or_default();
+        if shebang.contains("python") {
+            return Some("python");
+        }
+        if shebang.contains("node") {
+            return Some("javascript");
+        }
+        return Some("shell");
+    }
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some("json");
+    }
+    if !looks_like_code(text) {
+        return None;
+    }
+
+    let mut best: Option<(&'static str, usize)> = None;
+    for (language, markers) in MARKERS {
+        let score = markers.iter().filter(|m| text.contains(*m)).count();
+        // Earlier entries win ties (e.g. javascript over typescript-free snippets)
+        if score >= MIN_SCORE && best.is_none_or(|(_, top)| score > top) {
+            best = Some((language, score));
+        }
+    }
+    best.map(|(language, _)| language)
+}
+
+/**
+ * Heuristic: most non-empty lines end like statements or are indented
+ */
+pub fn looks_like_code(text: &str) -> bool {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.len() < 2 {
+        return false;
+    }
+
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim_end();
+            trimmed.ends_with([';', '{', '}', ')', ':', ','])
+                || line.starts_with("    ")
+                || line.starts_with('\t')
+        })
+        .count();
+    code_lines * 2 >= lines.len()
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * sRGB color parsed from a copied value
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f64, // 0.0 - 1.0
+}
+
+/**
+ * Output formats of convert_color_item
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+    Swift,
+    Android,
+}
+
+/**
+ * Swatch details for rendering a color item
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorSwatchModel {
+    pub hex: String,
+    pub rgb: String,
+    pub hsl: String,
+    pub alpha: f64,
+    pub luminance: f64, // WCAG relative luminance
+    pub is_dark: bool,  // prefer light text on top of the swatch
+}
+
+impl Color {
+    /**
+     * Parse #rgb(a), #rrggbb(aa), rgb()/rgba() and hsl()/hsla() values
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        if let Some(hex) = text.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+
+        let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+        let parts: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.len() != 3 && parts.len() != 4 {
+            return None;
+        }
+        let alpha = match parts.get(3) {
+            Some(part) => parse_unit(part, 1.0)?,
+            None => 1.0,
+        };
+
+        match name.trim() {
+            "rgb" | "rgba" => Some(Self {
+                r: channel(parts[0])?,
+                g: channel(parts[1])?,
+                b: channel(parts[2])?,
+                a: alpha,
+            }),
+            "hsl" | "hsla" => {
+                let hue = parts[0].trim_end_matches("deg").parse::<f64>().ok()?;
+                let saturation = parse_unit(parts[1], 100.0)?;
+                let lightness = parse_unit(parts[2], 100.0)?;
+                let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+                Some(Self { r, g, b, a: alpha })
+            }
+            _ => None,
+        }
+    }
+
+    /**
+     * #rrggbb, or #rrggbbaa when not opaque
+     */
+    pub fn to_hex(self) -> String {
+        let hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        if self.a < 1.0 {
+            format!("{}{:02x}", hex, (self.a * 255.0).round() as u8)
+        } else {
+            hex
+        }
+    }
+
+    pub fn format(self, format: ColorFormat) -> String {
+        let (r, g, b) = (self.r, self.g, self.b);
+        match format {
+            ColorFormat::Hex => self.to_hex(),
+            ColorFormat::Rgb if self.a < 1.0 => {
+                format!("rgba({}, {}, {}, {})", r, g, b, round(self.a, 3))
+            }
+            ColorFormat::Rgb => format!("rgb({}, {}, {})", r, g, b),
+            ColorFormat::Hsl => {
+                let (h, s, l) = self.to_hsl();
+                if self.a < 1.0 {
+                    format!("hsla({}, {}%, {}%, {})", h, s, l, round(self.a, 3))
+                } else {
+                    format!("hsl({}, {}%, {}%)", h, s, l)
+                }
+            }
+            ColorFormat::Swift => format!(
+                "UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.3})",
+                f64::from(r) / 255.0,
+                f64::from(g) / 255.0,
+                f64::from(b) / 255.0,
+                self.a
+            ),
+            ColorFormat::Android => format!(
+                "0x{:02X}{:02X}{:02X}{:02X}",
+                (self.a * 255.0).round() as u8,
+                r,
+                g,
+                b
+            ),
+        }
+    }
+
+    pub fn swatch(self) -> ColorSwatchModel {
+        let luminance = self.luminance();
+        ColorSwatchModel {
+            hex: self.to_hex(),
+            rgb: self.format(ColorFormat::Rgb),
+            hsl: self.format(ColorFormat::Hsl),
+            alpha: self.a,
+            luminance: round(luminance, 4),
+            // Contrast with white beats contrast with black below ~0.179
+            is_dark: luminance < 0.179,
+        }
+    }
+
+    /**
+     * Hue in degrees, saturation and lightness in whole percent
+     */
+    fn to_hsl(self) -> (i64, i64, i64) {
+        let r = f64::from(self.r) / 255.0;
+        let g = f64::from(self.g) / 255.0;
+        let b = f64::from(self.b) / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+
+        if d == 0.0 {
+            return (0, 0, (l * 100.0).round() as i64);
+        }
+
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * (((g - b) / d).rem_euclid(6.0))
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+
+        (
+            h.round() as i64 % 360,
+            (s * 100.0).round() as i64,
+            (l * 100.0).round() as i64,
+        )
+    }
+
+    fn luminance(self) -> f64 {
+        let linear = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|v| v * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0)?, digit(1)?, digit(2)?, 255),
+        4 => (digit(0)?, digit(1)?, digit(2)?, digit(3)?),
+        6 => (pair(0)?, pair(2)?, pair(4)?, 255),
+        8 => (pair(0)?, pair(2)?, pair(4)?, pair(6)?),
+        _ => return None,
+    };
+    Some(Color {
+        r,
+        g,
+        b,
+        a: f64::from(a) / 255.0,
+    })
+}
+
+/**
+ * An rgb() channel: 0-255 or a percentage
+ */
+fn channel(part: &str) -> Option<u8> {
+    let value = match part.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+        None => part.parse::<f64>().ok()?,
+    };
+    (0.0..=255.0).contains(&value).then(|| value.round() as u8)
+}
+
+/**
+ * A number scaled to 0.0-1.0; percentages divide by 100, bare numbers by `scale`
+ */
+fn parse_unit(part: &str, scale: f64) -> Option<f64> {
+    let value = match part.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => part.parse::<f64>().ok()? / scale,
+    };
+    (0.0..=1.0).contains(&value).then_some(value)
+}
+
+fn hsl_to_rgb(hue: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let to_byte = |v: f64| ((v + m) * 255.0).round() as u8;
+    (to_byte(r), to_byte(g), to_byte(b))
+}
+
+fn round(value: f64, places: i32) -> f64 {
+    let factor = 10f64.powi(places);
+    (value * factor).round() / factor
+}
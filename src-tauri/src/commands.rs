@@ -1,6 +1,73 @@
-use crate::db::DatabaseService;
-use crate::models::{ClipboardItemModel, ClipboardQueryFilter};
-use tauri::State;
+use crate::action_feedback;
+use crate::capabilities::{Capabilities, PlatformCapabilities};
+use crate::capture::{self, CaptureSource, CapturedItem};
+use crate::capture_filter::{CaptureFilterCounters, CaptureFilterStats};
+use crate::capture_health::{CaptureHealth, CaptureHealthModel};
+use crate::clipboard::{self, ClipboardBackend};
+use crate::color::{Color, ColorFormat, ColorSwatchModel};
+use crate::confirmation::{ConfirmationResult, Confirmations, DestructiveAction};
+use crate::db::{DatabaseService, SaveOutcome, MAX_ITEMS};
+use crate::dedup::Dedup;
+use crate::files;
+use crate::focus::{self, FocusMemory};
+use crate::history_lock::{self, HistoryLock, HistoryLockStatus};
+use crate::hooks::{self, CaptureHook};
+use crate::image_hash;
+use crate::item_shortcuts;
+use crate::keys;
+use crate::large_content;
+use crate::launcher::{AppLauncher, AppMatch};
+use crate::logging::{self, LogEntry};
+use crate::maintenance;
+use crate::metrics::{self, Metric, UsageMetrics};
+use crate::models::{
+    AppAliasModel, ArchiveFileModel, ArchiveReportModel, AuditLogEntryModel, ClipboardFileModel,
+    ClipboardItemModel, ClipboardItemPageModel, ClipboardQueryFilter, CompactReportModel,
+    ConsolidateReportModel, ItemContextImageModel, ItemShortcutModel, PickerPageModel, SortBy,
+    SortDirection, StorageBreakdownModel, StorageStatsModel, TimelineBucketModel, TimelineGrouping,
+    WebhookDeliveryModel, SOURCE_CLIPBOARD,
+};
+use crate::native_messaging;
+use crate::paste_format::PasteFormat;
+use crate::paste_stack::{PasteStack, PasteStackOrder, PasteStackState};
+use crate::picker::PickerSessions;
+use crate::primary_selection;
+use crate::qr;
+use crate::quick_actions::{self, QuickAction};
+use crate::recording::ScreenRecorder;
+use crate::report::{self, ReportFormat};
+use crate::scheduler::{JobStatus, Scheduler};
+use crate::screenshot::{self, ScreenshotMode};
+use crate::self_test::{self, SelfTestReport};
+use crate::shared_library::{self, SharedLibraryStatus};
+use crate::webhook::{self, WebhookConfig};
+use crate::window_state;
+use base64::Engine;
+use std::time::Instant;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+
+/**
+ * Record an audit entry; failures are logged, never surfaced to the caller
+ */
+fn audit(db: &DatabaseService, operation: &str, detail: Option<&str>, affected: usize) {
+    if let Err(e) = db.record_audit(operation, detail, affected) {
+        log::error!("Failed to record audit entry '{}': {}", operation, e);
+    }
+}
+
+/**
+ * Refuse to modify items of a read-only shared library
+ */
+fn ensure_own_item(db: &DatabaseService, id: &str) -> Result<(), String> {
+    match db.library_of(id).map_err(|e| e.to_string())? {
+        Some(library) => Err(format!(
+            "Item belongs to the read-only shared library '{}'",
+            library
+        )),
+        None => Ok(()),
+    }
+}
 
 /**
  * Initialize database (called on app startup)
@@ -15,93 +82,68 @@ pub fn init_database(_db_path: String) -> Result<String, String> {
  * Save clipboard item to database
  */
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn save_clipboard_item(
     id: String,
     content: String,
     item_type: String,
     image_base64: Option<String>,
     file_paths: Option<String>,
+    html: Option<String>,
+    rtf: Option<String>,
+    app: AppHandle,
     db: State<'_, DatabaseService>,
+    capture_health: State<'_, CaptureHealth>,
 ) -> Result<bool, String> {
-    eprintln!(
-        "[SAVE] Attempting to save item with id: {}, type: {}, content length: {}",
+    capture_health.record_capture(&app);
+    let captured = CapturedItem {
         id,
+        content,
         item_type,
-        content.len()
-    );
-
-    // Check for duplicate
-    eprintln!("[SAVE] Checking for duplicates...");
-    let is_duplicate = match db.check_duplicate(&content, &item_type) {
-        Ok(is_dup) => {
-            eprintln!("[SAVE] Duplicate check result: {}", is_dup);
-            is_dup
-        }
-        Err(e) => {
-            eprintln!("[SAVE] ERROR in duplicate check: {}", e);
-            return Err(format!("Failed to check duplicate: {}", e));
-        }
+        image_base64,
+        file_paths,
+        html,
+        rtf,
+        source_url: None,
+        source_title: None,
+        source: SOURCE_CLIPBOARD,
     };
-
-    eprintln!("[SAVE] Is duplicate: {}", is_duplicate);
-
-    if is_duplicate {
-        eprintln!("[SAVE] Item is duplicate, skipping save");
-        return Ok(false); // Duplicate item, not saved
-    }
-
-    let item = ClipboardItemModel::new(
-        id.clone(),
-        content.clone(),
-        item_type.clone(),
-        image_base64.clone(),
-        file_paths.clone(),
-    );
-
-    eprintln!("[SAVE] Creating item model: {:?}", id);
-
-    match db.create_item(item) {
-        Ok(rows) => {
-            eprintln!("[SAVE] Item created successfully, rows affected: {}", rows);
-        }
-        Err(e) => {
-            eprintln!("[SAVE] ERROR creating item: {}", e);
-            return Err(format!("Failed to create item: {}", e));
-        }
-    }
-
-    eprintln!("[SAVE] Enforcing max items limit...");
-    // Enforce max items limit (100)
-    match db.enforce_max_items(100) {
-        Ok(deleted) => {
-            eprintln!("[SAVE] Enforced max items, deleted {} old items", deleted);
-        }
-        Err(e) => {
-            eprintln!("[SAVE] ERROR enforcing max items: {}", e);
-        }
-    }
-
-    eprintln!("[SAVE] Item saved successfully");
-
-    Ok(true) // Item saved successfully
+    capture::save(&app, &db, captured, CaptureSource::Clipboard)
 }
 
 /**
  * Get clipboard items with filters
  */
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn get_clipboard_items(
     search: Option<String>,
     item_type: Option<String>,
+    language: Option<String>,
     is_pinned: Option<bool>,
+    since: Option<i64>,
+    until: Option<i64>,
+    sort_by: Option<SortBy>,
+    sort_direction: Option<SortDirection>,
+    include_archives: Option<bool>,
+    include_shared: Option<bool>,
     limit: u64,
     offset: u64,
     db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
 ) -> Result<Vec<ClipboardItemModel>, String> {
+    history_lock.check()?;
     let filter = ClipboardQueryFilter {
         search,
         item_type,
+        language,
         is_pinned,
+        since,
+        until,
+        sort_by: sort_by.unwrap_or(SortBy::Newest),
+        sort_direction,
+        include_archives: include_archives.unwrap_or(false),
+        include_shared: include_shared.unwrap_or(false),
         limit,
         offset,
     };
@@ -116,7 +158,9 @@ pub fn get_clipboard_items(
 pub fn get_clipboard_item(
     id: String,
     db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
 ) -> Result<Option<ClipboardItemModel>, String> {
+    history_lock.check()?;
     db.get_item(&id).map_err(|e| e.to_string())
 }
 
@@ -127,9 +171,16 @@ pub fn get_clipboard_item(
 pub fn update_clipboard_item(
     id: String,
     is_pinned: bool,
+    app: AppHandle,
     db: State<'_, DatabaseService>,
 ) -> Result<bool, String> {
+    ensure_own_item(&db, &id)?;
     db.update_item(&id, is_pinned).map_err(|e| e.to_string())?;
+
+    // Shortcuts are only for pinned items, unpinning drops them
+    if !is_pinned && db.unbind_item_shortcuts(&id).map_err(|e| e.to_string())? > 0 {
+        item_shortcuts::sync(&app, &db);
+    }
     Ok(true)
 }
 
@@ -137,24 +188,32 @@ pub fn update_clipboard_item(
  * Delete single item
  */
 #[tauri::command]
-pub fn delete_clipboard_item(id: String, db: State<'_, DatabaseService>) -> Result<bool, String> {
-    eprintln!("[DELETE] ========================================");
-    eprintln!("[DELETE] Attempting to delete item with id: {}", id);
-    eprintln!("[DELETE] ========================================");
+pub fn delete_clipboard_item(
+    id: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<bool, String> {
+    history_lock.check()?;
+    log::debug!("[DELETE] Attempting to delete item with id: {}", id);
+    ensure_own_item(&db, &id)?;
 
     match db.delete_item(&id) {
         Ok(rows_affected) => {
-            eprintln!("[DELETE] Successfully executed delete query");
-            eprintln!("[DELETE] Rows affected: {}", rows_affected);
+            log::debug!("[DELETE] Successfully executed delete query");
+            log::debug!("[DELETE] Rows affected: {}", rows_affected);
             if rows_affected == 0 {
-                eprintln!("[DELETE] WARNING: No rows were deleted. Item may not exist in DB");
+                log::warn!("[DELETE] No rows were deleted. Item may not exist in DB");
+            } else {
+                audit(&db, "delete", Some(&id), rows_affected);
+                maintenance::reclaim_if_needed(&db);
+                // Bindings went with the item, release their hotkeys
+                item_shortcuts::sync(&app, &db);
             }
-            eprintln!("[DELETE] ========================================");
             Ok(true)
         }
         Err(e) => {
-            eprintln!("[DELETE] ERROR: Failed to delete item {}: {}", id, e);
-            eprintln!("[DELETE] ========================================");
+            log::error!("[DELETE] Failed to delete item {}: {}", id, e);
             Err(format!("Failed to delete item: {}", e))
         }
     }
@@ -162,19 +221,68 @@ pub fn delete_clipboard_item(id: String, db: State<'_, DatabaseService>) -> Resu
 
 /**
  * Clear all clipboard history
+ * Without confirm=true only a confirmation token is issued, see confirm_action
  */
 #[tauri::command]
-pub fn clear_clipboard_history(db: State<'_, DatabaseService>) -> Result<bool, String> {
-    db.delete_all().map_err(|e| e.to_string())?;
-    log::info!("Cleared all clipboard history");
-    Ok(true)
+pub fn clear_clipboard_history(
+    confirm: Option<bool>,
+    db: State<'_, DatabaseService>,
+    confirmations: State<'_, Confirmations>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ConfirmationResult, String> {
+    history_lock.check()?;
+    if confirm != Some(true) {
+        return Ok(confirmations.request(DestructiveAction::ClearHistory));
+    }
+    run_destructive(&db, DestructiveAction::ClearHistory)?;
+    Ok(ConfirmationResult::Done)
+}
+
+/**
+ * Run the destructive action a pending token was issued for
+ */
+#[tauri::command]
+pub fn confirm_action(
+    token: String,
+    db: State<'_, DatabaseService>,
+    confirmations: State<'_, Confirmations>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ConfirmationResult, String> {
+    history_lock.check()?;
+    let action = confirmations.take(&token)?;
+    run_destructive(&db, action)?;
+    Ok(ConfirmationResult::Done)
+}
+
+/**
+ * Drop a pending confirmation token
+ */
+#[tauri::command]
+pub fn cancel_confirmation(token: String, confirmations: State<'_, Confirmations>) -> bool {
+    confirmations.cancel(&token)
+}
+
+fn run_destructive(db: &DatabaseService, action: DestructiveAction) -> Result<(), String> {
+    match action {
+        DestructiveAction::ClearHistory => {
+            let deleted = db.delete_all().map_err(|e| e.to_string())?;
+            audit(db, "clear", None, deleted);
+            maintenance::reclaim_if_needed(db);
+            log::info!("Cleared all clipboard history");
+        }
+    }
+    Ok(())
 }
 
 /**
  * Get total item count
  */
 #[tauri::command]
-pub fn get_clipboard_count(db: State<'_, DatabaseService>) -> Result<i64, String> {
+pub fn get_clipboard_count(
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<i64, String> {
+    history_lock.check()?;
     db.count_items().map_err(|e| e.to_string())
 }
 
@@ -184,14 +292,1551 @@ pub fn get_clipboard_count(db: State<'_, DatabaseService>) -> Result<i64, String
 #[tauri::command]
 pub fn load_initial_history(
     db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
 ) -> Result<Vec<ClipboardItemModel>, String> {
+    history_lock.check()?;
     let filter = ClipboardQueryFilter {
         search: None,
         item_type: None,
         is_pinned: None,
+        since: None,
+        until: None,
         limit: 100,
-        offset: 0,
+        ..Default::default()
     };
 
     db.get_items(filter).map_err(|e| e.to_string())
 }
+
+/**
+ * Merge several text items into a new item (in the given order)
+ */
+#[tauri::command]
+pub fn merge_clipboard_items(
+    ids: Vec<String>,
+    separator: Option<String>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Option<ClipboardItemModel>, String> {
+    history_lock.check()?;
+    let separator = separator.unwrap_or_else(|| "\n".to_string());
+    db.merge_items(&ids, &separator, MAX_ITEMS)
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Append copied text to the most recent text item instead of creating a new one
+ */
+#[tauri::command]
+pub fn append_to_clipboard_item(
+    content: String,
+    separator: Option<String>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Option<ClipboardItemModel>, String> {
+    history_lock.check()?;
+    let separator = separator.unwrap_or_else(|| "\n".to_string());
+
+    let id = match db
+        .append_to_latest(&content, &separator, MAX_ITEMS)
+        .map_err(|e| e.to_string())?
+    {
+        Some(id) => id,
+        None => {
+            // Nothing to append to (or only a large item's preview), start a new item
+            let item = ClipboardItemModel::new(
+                uuid::Uuid::new_v4().to_string(),
+                content,
+                "text".to_string(),
+                None,
+                None,
+            );
+            match db
+                .save_item(&item, &Dedup::from_settings(&db), 0, MAX_ITEMS)
+                .map_err(|e| e.to_string())?
+            {
+                SaveOutcome::Saved { .. } => item.id,
+                SaveOutcome::Duplicate { existing, .. } => existing,
+            }
+        }
+    };
+
+    db.get_item(&id).map_err(|e| e.to_string())
+}
+
+/**
+ * Enter paste stack mode: queue the next `capacity` copies
+ */
+#[tauri::command]
+pub fn start_paste_stack(
+    capacity: u32,
+    order: Option<PasteStackOrder>,
+    paste_stack: State<'_, PasteStack>,
+) -> Result<PasteStackState, String> {
+    Ok(paste_stack.start(capacity, order.unwrap_or(PasteStackOrder::Fifo)))
+}
+
+/**
+ * Stop collecting copies, keeping the queued items
+ */
+#[tauri::command]
+pub fn stop_paste_stack(paste_stack: State<'_, PasteStack>) -> Result<PasteStackState, String> {
+    Ok(paste_stack.stop())
+}
+
+/**
+ * Get the paste stack state and queued items
+ */
+#[tauri::command]
+pub fn get_paste_stack(
+    paste_stack: State<'_, PasteStack>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<ClipboardItemModel>, String> {
+    history_lock.check()?;
+    let mut items = Vec::new();
+    for id in paste_stack.snapshot().item_ids {
+        if let Some(item) = db.get_item(&id).map_err(|e| e.to_string())? {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
+
+/**
+ * Get the paste stack mode, order and remaining capacity
+ */
+#[tauri::command]
+pub fn get_paste_stack_state(
+    paste_stack: State<'_, PasteStack>,
+) -> Result<PasteStackState, String> {
+    Ok(paste_stack.snapshot())
+}
+
+/**
+ * Pop the next item from the paste stack and paste it into the previously focused app
+ * Returns the pasted item, None once the stack is empty
+ */
+#[tauri::command]
+pub async fn pop_paste_stack(
+    app: AppHandle,
+    paste_stack: State<'_, PasteStack>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+    focus_memory: State<'_, FocusMemory>,
+) -> Result<Option<ClipboardItemModel>, String> {
+    history_lock.check()?;
+    // Skip items deleted from history since they were queued
+    let mut next = None;
+    while let Some(id) = paste_stack.pop() {
+        if let Some(item) = db.get_item(&id).map_err(|e| e.to_string())? {
+            next = Some(item);
+            break;
+        }
+    }
+    let Some(item) = next else {
+        return Ok(None);
+    };
+
+    clipboard::write_item(clipboard_backend.provider(), &db, &item, true)?;
+    hide_main_window(app.clone())?;
+
+    // Focus changes and keystrokes wait on the target app, keep them off the main thread
+    let previous = focus_memory.previous();
+    let pasted =
+        tauri::async_runtime::spawn_blocking(move || focus::return_and_paste(previous.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?;
+    action_feedback::report(&app, "Paste stack", pasted)?;
+
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    metrics::record(&db, Metric::Paste, None);
+    Ok(Some(item))
+}
+
+/**
+ * Reorder queued paste stack items
+ */
+#[tauri::command]
+pub fn reorder_paste_stack(
+    item_ids: Vec<String>,
+    paste_stack: State<'_, PasteStack>,
+) -> Result<PasteStackState, String> {
+    Ok(paste_stack.reorder(item_ids))
+}
+
+/**
+ * Clear the paste stack and leave stack mode
+ */
+#[tauri::command]
+pub fn clear_paste_stack(paste_stack: State<'_, PasteStack>) -> Result<PasteStackState, String> {
+    Ok(paste_stack.clear())
+}
+
+/**
+ * Take a screenshot and save it to history as an image item
+ */
+#[tauri::command]
+pub async fn take_screenshot(
+    mode: ScreenshotMode,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+) -> Result<ClipboardItemModel, String> {
+    // Region/window capture waits on the user's selection, keep it off the main thread
+    let png = tauri::async_runtime::spawn_blocking(move || screenshot::capture(mode))
+        .await
+        .map_err(|e| e.to_string())?;
+    let png = action_feedback::report(&app, "Take screenshot", png)?;
+
+    let image_base64 = base64::engine::general_purpose::STANDARD.encode(png);
+    let mut item = ClipboardItemModel::new(
+        uuid::Uuid::new_v4().to_string(),
+        "[Image]".to_string(),
+        "image_base64".to_string(),
+        None,
+        None,
+    );
+    item.image_hash = image_hash::dhash(&image_base64);
+    item.image_base64 = Some(image_base64);
+
+    // Same dedup and max items limit as copied images
+    let threshold = capture::image_dedup_threshold(&db);
+    match db
+        .save_item(&item, &Dedup::from_settings(&db), threshold, MAX_ITEMS)
+        .map_err(|e| e.to_string())?
+    {
+        SaveOutcome::Saved { evicted } => {
+            if evicted > 0 {
+                audit(&db, "evict", Some("max items limit"), evicted);
+            }
+            log::info!("Saved {:?} screenshot to history", mode);
+            Ok(item)
+        }
+        SaveOutcome::Duplicate { existing, .. } => {
+            log::info!("{:?} screenshot is a duplicate of {}", mode, existing);
+            db.get_item(&existing)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Item not found".to_string())
+        }
+    }
+}
+
+/**
+ * Start a screen recording, returns the output file path
+ * Defaults to the user's videos folder when no folder is given
+ */
+#[tauri::command]
+pub async fn start_screen_recording(
+    output_dir: Option<String>,
+    app: AppHandle,
+) -> Result<String, String> {
+    let output_dir = match output_dir {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => directories::UserDirs::new()
+            .and_then(|dirs| dirs.video_dir().map(|dir| dir.to_path_buf()))
+            .unwrap_or_else(|| crate::app_data_dir().join("recordings")),
+    };
+
+    // Starting waits briefly to catch a recorder that exits at once, keep it off the main thread
+    let recorder_app = app.clone();
+    let started = tauri::async_runtime::spawn_blocking(move || {
+        recorder_app.state::<ScreenRecorder>().start(output_dir)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    action_feedback::report(
+        &app,
+        "Start screen recording",
+        started.map(|path| path.to_string_lossy().to_string()),
+    )
+}
+
+/**
+ * Stop the screen recording and save it to history as a file item
+ */
+#[tauri::command]
+pub async fn stop_screen_recording(
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+) -> Result<ClipboardItemModel, String> {
+    // Waiting for the recorder to finalize the file can take seconds
+    let recorder_app = app.clone();
+    let stopped =
+        tauri::async_runtime::spawn_blocking(move || recorder_app.state::<ScreenRecorder>().stop())
+            .await
+            .map_err(|e| e.to_string())?;
+    let path = action_feedback::report(&app, "Stop screen recording", stopped)?;
+    let path_str = path.to_string_lossy().to_string();
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path_str.clone());
+
+    let item = ClipboardItemModel::new(
+        uuid::Uuid::new_v4().to_string(),
+        name,
+        "file".to_string(),
+        None,
+        Some(serde_json::to_string(&[&path_str]).map_err(|e| e.to_string())?),
+    );
+
+    // Same dedup and max items limit as copied files
+    match db
+        .save_item(&item, &Dedup::from_settings(&db), 0, MAX_ITEMS)
+        .map_err(|e| e.to_string())?
+    {
+        SaveOutcome::Saved { evicted } => {
+            if evicted > 0 {
+                audit(&db, "evict", Some("max items limit"), evicted);
+            }
+            let file = files::inspect(&item.id, &path_str);
+            if let Err(e) = db.save_item_files(&item.id, &[file]) {
+                log::error!("Failed to save recording file metadata: {}", e);
+            }
+            log::info!("Saved screen recording {} to history", path.display());
+            Ok(item)
+        }
+        SaveOutcome::Duplicate { existing, .. } => {
+            log::info!("Screen recording is a duplicate of {}", existing);
+            db.get_item(&existing)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Item not found".to_string())
+        }
+    }
+}
+
+/**
+ * Whether a screen recording is running
+ */
+#[tauri::command]
+pub fn is_screen_recording(recorder: State<'_, ScreenRecorder>) -> Result<bool, String> {
+    Ok(recorder.is_recording())
+}
+
+/**
+ * Show and focus the main window (e.g. after a headless launch)
+ */
+#[tauri::command]
+pub fn show_main_window(app: AppHandle) -> Result<bool, String> {
+    window_state::show_main(&app)?;
+    Ok(true)
+}
+
+/**
+ * Hide the main window, the app keeps running in the background
+ */
+#[tauri::command]
+pub fn hide_main_window(app: AppHandle) -> Result<bool, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    if let Some(db) = app.try_state::<DatabaseService>() {
+        if let Err(e) = window_state::save(&window, &db) {
+            log::error!("Failed to save main window state: {}", e);
+        }
+    }
+
+    window.hide().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Forget the saved main window geometry and re-center it at the default size
+ */
+#[tauri::command]
+pub fn reset_window_state(app: AppHandle, db: State<'_, DatabaseService>) -> Result<bool, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    window_state::reset(&window, &db)?;
+    Ok(true)
+}
+
+/**
+ * Get all app settings (the master password hash is left out)
+ */
+#[tauri::command]
+pub fn get_settings(
+    db: State<'_, DatabaseService>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let mut settings = db.get_settings().map_err(|e| e.to_string())?;
+    settings.remove(history_lock::PASSWORD_SETTING);
+    Ok(settings)
+}
+
+/**
+ * Update a single app setting
+ */
+#[tauri::command]
+pub fn set_setting(
+    key: String,
+    value: String,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    if history_lock::is_protected_setting(&key) {
+        return Err(format!("Setting '{}' is managed by the history lock", key));
+    }
+    db.set_setting(&key, &value).map_err(|e| e.to_string())?;
+    if logging::is_logging_setting(&key) {
+        logging::apply_settings(&db);
+    }
+    Ok(true)
+}
+
+/**
+ * Put an item's text in the PRIMARY selection for middle-click paste (Linux)
+ */
+#[tauri::command]
+pub fn paste_primary(
+    id: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<bool, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    let text = large_content::full_content(&db, &item)?;
+    action_feedback::report(
+        &app,
+        "Paste to primary selection",
+        primary_selection::write_primary(&text),
+    )?;
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    metrics::record(&db, Metric::Paste, None);
+    Ok(true)
+}
+
+/**
+ * Write a history item to the system clipboard
+ * `rich` restores HTML/RTF flavors, otherwise only plain text is written
+ * `format` converts the text first (HTML to Markdown, line endings, ...)
+ */
+#[tauri::command]
+pub fn set_clipboard_from_item(
+    id: String,
+    rich: Option<bool>,
+    format: Option<PasteFormat>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+) -> Result<bool, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    match format {
+        Some(format) => {
+            clipboard::write_converted(clipboard_backend.provider(), &db, &item, format)?
+        }
+        None => clipboard::write_item(
+            clipboard_backend.provider(),
+            &db,
+            &item,
+            rich.unwrap_or(true),
+        )?,
+    }
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    metrics::record(&db, Metric::Paste, None);
+    Ok(true)
+}
+
+/**
+ * Get the files of a file item, re-checking that they still exist
+ */
+#[tauri::command]
+pub fn get_clipboard_item_files(
+    id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<ClipboardFileModel>, String> {
+    history_lock.check()?;
+    let stored = db.get_item_files(&id).map_err(|e| e.to_string())?;
+
+    // Items saved before file rows existed only have the JSON blob
+    let paths: Vec<String> = if stored.is_empty() {
+        db.get_item(&id)
+            .map_err(|e| e.to_string())?
+            .and_then(|item| item.file_paths)
+            .map(|paths| files::parse_paths(&paths))
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default()
+    } else {
+        stored.iter().map(|file| file.path.clone()).collect()
+    };
+
+    let refreshed: Vec<_> = paths.iter().map(|path| files::inspect(&id, path)).collect();
+
+    let changed = stored.len() != refreshed.len()
+        || stored
+            .iter()
+            .zip(&refreshed)
+            .any(|(before, now)| before.exists != now.exists || before.size != now.size);
+    if changed {
+        db.save_item_files(&id, &refreshed)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(refreshed)
+}
+
+/**
+ * Get item counts grouped by day or week, for timeline navigation
+ */
+#[tauri::command]
+pub fn get_history_timeline(
+    group_by: TimelineGrouping,
+    since: Option<i64>,
+    until: Option<i64>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<TimelineBucketModel>, String> {
+    history_lock.check()?;
+    db.get_timeline(group_by, since, until)
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Bind a shortcut to a pinned item
+ */
+#[tauri::command]
+pub fn bind_item_shortcut(
+    shortcut: String,
+    item_id: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+) -> Result<ItemShortcutModel, String> {
+    let shortcut = keys::normalize_shortcut(&shortcut)?;
+    let item = db
+        .get_item(&item_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    if !item.is_pinned {
+        return Err("Only pinned items can be bound to a shortcut".to_string());
+    }
+
+    item_shortcuts::register(&app, &shortcut)?;
+    if let Err(e) = db.bind_shortcut(&shortcut, &item_id) {
+        item_shortcuts::sync(&app, &db);
+        return Err(e.to_string());
+    }
+
+    Ok(ItemShortcutModel {
+        shortcut,
+        item_id,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/**
+ * Remove a shortcut binding
+ */
+#[tauri::command]
+pub fn unbind_item_shortcut(
+    shortcut: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    let shortcut = keys::normalize_shortcut(&shortcut)?;
+    let rows = db.unbind_shortcut(&shortcut).map_err(|e| e.to_string())?;
+    item_shortcuts::unregister(&app, &shortcut);
+    Ok(rows > 0)
+}
+
+/**
+ * List shortcut bindings
+ */
+#[tauri::command]
+pub fn get_item_shortcuts(
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<ItemShortcutModel>, String> {
+    history_lock.check()?;
+    db.get_shortcuts().map_err(|e| e.to_string())
+}
+
+/**
+ * Fire a shortcut: write its bound item to the clipboard
+ * Registered hotkeys paste on their own (see item_shortcuts), this is for in-app triggers
+ */
+#[tauri::command]
+pub fn trigger_item_shortcut(
+    shortcut: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+) -> Result<Option<ClipboardItemModel>, String> {
+    history_lock.check()?;
+    let shortcut = keys::normalize_shortcut(&shortcut)?;
+    let Some(item) = db.get_shortcut_item(&shortcut).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+
+    action_feedback::report(
+        &app,
+        "Paste shortcut",
+        clipboard::write_item(clipboard_backend.provider(), &db, &item, true),
+    )?;
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    Ok(Some(item))
+}
+
+/**
+ * Get the audit trail of destructive operations, newest first
+ */
+#[tauri::command]
+pub fn get_audit_log(
+    limit: Option<u64>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<AuditLogEntryModel>, String> {
+    history_lock.check()?;
+    db.get_audit_log(limit.unwrap_or(100))
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Back up the database to a file (path picked via the dialog plugin)
+ */
+#[tauri::command]
+pub fn backup_database(
+    path: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<bool, String> {
+    history_lock.check()?;
+    db.backup_to(std::path::Path::new(&path))
+        .map_err(|e| format!("Failed to back up database: {}", e))?;
+    log::info!("Database backed up to {}", path);
+    let count = db.count_items().unwrap_or_default() as usize;
+    audit(&db, "backup", Some(&path), count);
+    Ok(true)
+}
+
+/**
+ * Restore the database from a backup file
+ * A snapshot of the current database is taken first; its path is returned
+ */
+#[tauri::command]
+pub fn restore_database(
+    path: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<String, String> {
+    // A restored database brings its own password hash, so only an unlocked session may swap it in
+    history_lock.check()?;
+    let snapshot_dir = db
+        .path()
+        .parent()
+        .map(|dir| dir.join("backups"))
+        .ok_or_else(|| "Failed to resolve backups folder".to_string())?;
+    std::fs::create_dir_all(&snapshot_dir).map_err(|e| e.to_string())?;
+
+    let snapshot = snapshot_dir.join(format!(
+        "pre-restore-{}.db",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    db.backup_to(&snapshot)
+        .map_err(|e| format!("Failed to snapshot database before restore: {}", e))?;
+
+    db.restore_from(std::path::Path::new(&path))
+        .map_err(|e| format!("Failed to restore database: {}", e))?;
+
+    audit(&db, "restore", Some(&path), 0);
+    log::info!("Database restored from {}", path);
+    Ok(snapshot.to_string_lossy().to_string())
+}
+
+/**
+ * Check database integrity, returns the problems found (empty when healthy)
+ */
+#[tauri::command]
+pub fn check_database_integrity(
+    full: Option<bool>,
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<String>, String> {
+    db.check_integrity(full.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * VACUUM the database, returns sizes before and after
+ */
+#[tauri::command]
+pub fn compact_database(db: State<'_, DatabaseService>) -> Result<CompactReportModel, String> {
+    let report = maintenance::compact(&db).map_err(|e| e.to_string())?;
+    log::info!(
+        "Compacted database: {} -> {} bytes",
+        report.size_before,
+        report.size_after
+    );
+    Ok(report)
+}
+
+/**
+ * Get database page usage (size and reclaimable free space)
+ */
+#[tauri::command]
+pub fn get_database_storage_stats(
+    db: State<'_, DatabaseService>,
+) -> Result<StorageStatsModel, String> {
+    db.storage_stats().map_err(|e| e.to_string())
+}
+
+/**
+ * Get one page of lightweight item summaries (no image data or full content)
+ * Pass the returned next_cursor to fetch the following page
+ */
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn get_items_page(
+    search: Option<String>,
+    item_type: Option<String>,
+    language: Option<String>,
+    is_pinned: Option<bool>,
+    cursor: Option<String>,
+    limit: u64,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ClipboardItemPageModel, String> {
+    history_lock.check()?;
+    let filter = ClipboardQueryFilter {
+        search,
+        item_type,
+        language,
+        is_pinned,
+        since: None,
+        until: None,
+        limit,
+        ..Default::default()
+    };
+
+    db.get_items_page(&filter, cursor.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Stream the whole history as summary pages over a channel
+ * Returns the number of items sent
+ */
+#[tauri::command]
+pub async fn stream_clipboard_items(
+    page_size: u64,
+    on_page: Channel<ClipboardItemPageModel>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<u64, String> {
+    history_lock.check()?;
+    let filter = ClipboardQueryFilter {
+        limit: page_size.max(1),
+        ..Default::default()
+    };
+
+    let mut cursor: Option<String> = None;
+    let mut sent = 0;
+    loop {
+        let page = db
+            .get_items_page(&filter, cursor.as_deref())
+            .map_err(|e| e.to_string())?;
+
+        sent += page.items.len() as u64;
+        cursor = page.next_cursor.clone();
+        on_page.send(page).map_err(|e| e.to_string())?;
+
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(sent)
+}
+
+/**
+ * Get history disk usage broken down by item type
+ */
+#[tauri::command]
+pub fn get_storage_breakdown(
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<StorageBreakdownModel>, String> {
+    history_lock.check()?;
+    db.get_storage_breakdown().map_err(|e| e.to_string())
+}
+
+/**
+ * Get how many copies each capture filter has skipped since launch
+ */
+#[tauri::command]
+pub fn get_capture_filter_stats(
+    filter_counters: State<'_, CaptureFilterCounters>,
+) -> Result<CaptureFilterStats, String> {
+    Ok(filter_counters.snapshot())
+}
+
+/**
+ * Reset the capture filter counters, returning the values before reset
+ */
+#[tauri::command]
+pub fn reset_capture_filter_stats(
+    filter_counters: State<'_, CaptureFilterCounters>,
+) -> Result<CaptureFilterStats, String> {
+    Ok(filter_counters.reset())
+}
+
+/**
+ * Render an item's text as a QR code, returned as a base64 PNG
+ */
+#[tauri::command]
+pub fn get_item_qr(
+    id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<String, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    if item.image_base64.is_some() {
+        return Err("Image items cannot be shared as a QR code".to_string());
+    }
+
+    // content is only a preview for externalized items, encode the full text
+    if let Some(size) = item
+        .content_size
+        .filter(|size| *size as usize > qr::MAX_QR_BYTES)
+    {
+        return Err(format!(
+            "Item text is too long for a QR code ({} bytes, max {})",
+            size,
+            qr::MAX_QR_BYTES
+        ));
+    }
+    let text = large_content::full_content(&db, &item)?;
+    let png = qr::render_png(&text)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/**
+ * Get the configured capture hooks
+ */
+#[tauri::command]
+pub fn get_capture_hooks(db: State<'_, DatabaseService>) -> Result<Vec<CaptureHook>, String> {
+    Ok(hooks::load(&db))
+}
+
+/**
+ * Replace the capture hooks, run in order on every new text item
+ */
+#[tauri::command]
+pub fn set_capture_hooks(
+    hooks: Vec<CaptureHook>,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    hooks::validate(&hooks)?;
+    let value = serde_json::to_string(&hooks).map_err(|e| e.to_string())?;
+    db.set_setting(hooks::HOOKS_SETTING, &value)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Get the outbound webhook configuration
+ */
+#[tauri::command]
+pub fn get_webhook_config(db: State<'_, DatabaseService>) -> Result<Option<WebhookConfig>, String> {
+    Ok(webhook::load(&db))
+}
+
+/**
+ * Set the outbound webhook; an empty url disables it
+ */
+#[tauri::command]
+pub fn set_webhook_config(
+    config: WebhookConfig,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    webhook::validate(&config)?;
+    let value = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    db.set_setting(webhook::WEBHOOK_SETTING, &value)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Get recent webhook deliveries, newest first
+ */
+#[tauri::command]
+pub fn get_webhook_deliveries(
+    limit: Option<u64>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<WebhookDeliveryModel>, String> {
+    history_lock.check()?;
+    db.get_webhook_deliveries(limit.unwrap_or(100))
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Install the native messaging host manifest so a browser extension can connect
+ * Returns the manifest path
+ */
+#[tauri::command]
+pub fn install_native_messaging_host(
+    browser: native_messaging::Browser,
+    extension_id: String,
+) -> Result<String, String> {
+    native_messaging::install(browser, &extension_id, &crate::app_data_dir())
+        .map(|path| path.display().to_string())
+}
+
+/**
+ * Remove the native messaging host manifest for a browser
+ */
+#[tauri::command]
+pub fn uninstall_native_messaging_host(browser: native_messaging::Browser) -> Result<bool, String> {
+    native_messaging::uninstall(browser, &crate::app_data_dir())
+}
+
+/**
+ * Merge near-duplicate history items, returns how many rows were merged
+ */
+#[tauri::command]
+pub fn consolidate_duplicates(
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ConsolidateReportModel, String> {
+    history_lock.check()?;
+    let report = db.consolidate_duplicates().map_err(|e| e.to_string())?;
+
+    if report.merged > 0 {
+        audit(&db, "consolidate", None, report.merged);
+        maintenance::reclaim_if_needed(&db);
+        // Bindings that couldn't move to the kept item went with the merged ones
+        item_shortcuts::sync(&app, &db);
+    }
+    Ok(report)
+}
+
+/**
+ * Move unpinned items older than `older_than_months` into monthly archive files
+ */
+#[tauri::command]
+pub fn archive_history(
+    older_than_months: u32,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ArchiveReportModel, String> {
+    history_lock.check()?;
+    if older_than_months == 0 {
+        return Err("older_than_months must be at least 1".to_string());
+    }
+    maintenance::archive_older_than(&db, older_than_months).map_err(|e| e.to_string())
+}
+
+/**
+ * List the monthly archive files, newest first
+ */
+#[tauri::command]
+pub fn list_archives(db: State<'_, DatabaseService>) -> Result<Vec<ArchiveFileModel>, String> {
+    Ok(db.list_archives())
+}
+
+/**
+ * Export matching history as a Markdown or HTML document grouped by day
+ */
+#[tauri::command]
+pub fn export_report(
+    format: ReportFormat,
+    filter: Option<ClipboardQueryFilter>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<String, String> {
+    history_lock.check()?;
+    let items = db
+        .get_items(filter.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+    let detail = match format {
+        ReportFormat::Markdown => "markdown",
+        ReportFormat::Html => "html",
+    };
+    audit(&db, "export_report", Some(detail), items.len());
+    Ok(report::render(&items, format))
+}
+
+/**
+ * Get the quick actions available for an item, the first is the double-click default
+ */
+#[tauri::command]
+pub fn get_item_quick_actions(
+    id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Vec<QuickAction>, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    Ok(quick_actions::available(&item))
+}
+
+/**
+ * Run a quick action on an item (open URL, reveal file, open image, compose mail)
+ * Without an action the item's default action is used
+ */
+#[tauri::command]
+pub fn execute_item_quick_action(
+    id: String,
+    action: Option<QuickAction>,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<QuickAction, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    let action = match action {
+        Some(action) => action,
+        None => *quick_actions::available(&item)
+            .first()
+            .ok_or_else(|| "No quick action for this item".to_string())?,
+    };
+
+    let started = Instant::now();
+    action_feedback::report(
+        &app,
+        action.label(),
+        quick_actions::execute(&app, &item, action),
+    )?;
+    metrics::record(&db, Metric::Action(action.name()), Some(started.elapsed()));
+    Ok(action)
+}
+
+/**
+ * Convert a color item to another notation (hex, rgb, hsl, swift, android)
+ * With `copy` set the converted value is also written to the clipboard
+ */
+#[tauri::command]
+pub fn convert_color_item(
+    id: String,
+    format: ColorFormat,
+    copy: Option<bool>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+) -> Result<String, String> {
+    history_lock.check()?;
+    let color = item_color(&db, &id)?;
+    let converted = color.format(format);
+
+    if copy.unwrap_or(false) {
+        clipboard_backend.provider().write_text(converted.clone())?;
+    }
+    Ok(converted)
+}
+
+/**
+ * Get swatch metadata (notations, luminance, text contrast) of a color item
+ */
+#[tauri::command]
+pub fn get_color_swatch(
+    id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<ColorSwatchModel, String> {
+    history_lock.check()?;
+    Ok(item_color(&db, &id)?.swatch())
+}
+
+fn item_color(db: &DatabaseService, id: &str) -> Result<Color, String> {
+    let item = db
+        .get_item(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    item.color
+        .as_deref()
+        .and_then(Color::parse)
+        .ok_or_else(|| "Item is not a color".to_string())
+}
+
+/**
+ * Get whether history is locked and how it can be unlocked
+ */
+#[tauri::command]
+pub fn get_history_lock_status(
+    history_lock: State<'_, HistoryLock>,
+    db: State<'_, DatabaseService>,
+) -> Result<HistoryLockStatus, String> {
+    Ok(history_lock.status(&db))
+}
+
+/**
+ * Set, change or remove (new_password None) the master password
+ * Removing the password also unlocks history
+ */
+#[tauri::command]
+pub fn set_history_password(
+    current_password: Option<String>,
+    new_password: Option<String>,
+    app: AppHandle,
+    history_lock: State<'_, HistoryLock>,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    history_lock::set_password(&db, current_password.as_deref(), new_password.as_deref())?;
+
+    let detail = if new_password.is_some() {
+        "set"
+    } else {
+        "removed"
+    };
+    audit(&db, "set_history_password", Some(detail), 0);
+    if new_password.is_none() {
+        history_lock.release(&app, &db);
+    }
+    Ok(true)
+}
+
+/**
+ * Lock history until unlock_history succeeds
+ */
+#[tauri::command]
+pub fn lock_history(
+    app: AppHandle,
+    history_lock: State<'_, HistoryLock>,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    history_lock.lock(&app, &db)?;
+    Ok(true)
+}
+
+/**
+ * Unlock history with the master password, or the OS biometric prompt without one
+ * Returns false when verification fails
+ */
+#[tauri::command]
+pub async fn unlock_history(
+    password: Option<String>,
+    app: AppHandle,
+    history_lock: State<'_, HistoryLock>,
+    db: State<'_, DatabaseService>,
+) -> Result<bool, String> {
+    let unlocked = history_lock.unlock(&app, &db, password.as_deref())?;
+    if !unlocked {
+        audit(&db, "unlock_history_failed", None, 0);
+    }
+    Ok(unlocked)
+}
+
+/**
+ * Get the screenshot of the window an item was copied from, if one was captured
+ */
+#[tauri::command]
+pub fn get_item_context_image(
+    id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<Option<ItemContextImageModel>, String> {
+    history_lock.check()?;
+    db.get_context_image(&id).map_err(|e| e.to_string())
+}
+
+/**
+ * Check a shortcut and return its canonical form ("Cmd+Page Up" -> "super+pageup")
+ */
+#[tauri::command]
+pub fn validate_shortcut(shortcut: String) -> Result<String, String> {
+    keys::normalize_shortcut(&shortcut)
+}
+
+/**
+ * Get every key name accepted in shortcuts
+ */
+#[tauri::command]
+pub fn get_key_names() -> Vec<String> {
+    keys::key_names()
+}
+
+/**
+ * Stream the full text of an item in chunks, including text stored outside the database
+ * Returns the number of bytes sent
+ */
+#[tauri::command]
+pub async fn get_item_full_content(
+    id: String,
+    on_chunk: Channel<String>,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<u64, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    large_content::stream_full_content(&db, &item, &on_chunk)
+}
+
+/**
+ * List background jobs with their schedule and last run
+ */
+#[tauri::command]
+pub fn get_jobs(scheduler: State<'_, Scheduler>) -> Result<Vec<JobStatus>, String> {
+    Ok(scheduler.statuses())
+}
+
+/**
+ * Stop a background job from running on its schedule
+ */
+#[tauri::command]
+pub fn pause_job(
+    name: String,
+    db: State<'_, DatabaseService>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<JobStatus, String> {
+    scheduler.set_paused(&db, &name, true)
+}
+
+/**
+ * Put a paused background job back on its schedule
+ */
+#[tauri::command]
+pub fn resume_job(
+    name: String,
+    db: State<'_, DatabaseService>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<JobStatus, String> {
+    scheduler.set_paused(&db, &name, false)
+}
+
+/**
+ * Run a background job now, even while paused
+ * Housekeeping jobs archive and evict history, so this is refused while locked
+ */
+#[tauri::command]
+pub fn run_job_now(
+    name: String,
+    scheduler: State<'_, Scheduler>,
+    history_lock: State<'_, HistoryLock>,
+) -> Result<(), String> {
+    history_lock.check()?;
+    scheduler.run_now(&name)
+}
+
+/**
+ * Recent log records for diagnostics, oldest first
+ * `level` keeps records at that level or more severe; `limit` defaults to 200
+ */
+#[tauri::command]
+pub fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    logging::recent(level.as_deref(), limit.unwrap_or(200))
+}
+
+/**
+ * Change how verbose logging is (error, warn, info, debug, trace or off)
+ */
+#[tauri::command]
+pub fn set_log_level(level: String, db: State<'_, DatabaseService>) -> Result<(), String> {
+    logging::set_level(&db, &level)
+}
+
+/**
+ * Start a history picker session and return its first page
+ * Later keystrokes go through filter_picker instead of re-querying from scratch
+ */
+#[tauri::command]
+pub fn start_picker_session(
+    item_type: Option<String>,
+    language: Option<String>,
+    is_pinned: Option<bool>,
+    page_size: usize,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    picker: State<'_, PickerSessions>,
+) -> Result<PickerPageModel, String> {
+    history_lock.check()?;
+    let filter = ClipboardQueryFilter {
+        item_type,
+        language,
+        is_pinned,
+        ..Default::default()
+    };
+    picker.start(&db, filter, page_size)
+}
+
+/**
+ * Fuzzy filter a picker session; returns null when a newer keystroke superseded this call
+ */
+#[tauri::command]
+pub async fn filter_picker(
+    session_id: String,
+    text: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    picker: State<'_, PickerSessions>,
+) -> Result<Option<PickerPageModel>, String> {
+    history_lock.check()?;
+    picker.filter(&db, &session_id, text).await
+}
+
+/**
+ * Next page of a picker session's matches
+ */
+#[tauri::command]
+pub fn picker_next_page(
+    session_id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    picker: State<'_, PickerSessions>,
+) -> Result<PickerPageModel, String> {
+    history_lock.check()?;
+    picker.turn_page(&db, &session_id, 1)
+}
+
+/**
+ * Previous page of a picker session's matches
+ */
+#[tauri::command]
+pub fn picker_prev_page(
+    session_id: String,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    picker: State<'_, PickerSessions>,
+) -> Result<PickerPageModel, String> {
+    history_lock.check()?;
+    picker.turn_page(&db, &session_id, -1)
+}
+
+/**
+ * End a picker session when the picker closes
+ */
+#[tauri::command]
+pub fn close_picker_session(session_id: String, picker: State<'_, PickerSessions>) -> bool {
+    picker.close(&session_id)
+}
+
+/**
+ * Put an item on the clipboard converted to another format
+ */
+#[tauri::command]
+pub fn paste_as(
+    id: String,
+    format: PasteFormat,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+) -> Result<bool, String> {
+    set_clipboard_from_item(id, None, Some(format), db, history_lock, clipboard_backend)
+}
+
+/**
+ * Clipboard watcher health as seen by the capture watchdog
+ */
+#[tauri::command]
+pub fn get_capture_health(capture_health: State<'_, CaptureHealth>) -> CaptureHealthModel {
+    capture_health.snapshot()
+}
+
+/**
+ * Local usage counters (captures, pastes, actions) for the last `days` days
+ * Only collected while the usage_metrics_enabled setting is on
+ */
+#[tauri::command]
+pub fn get_usage_metrics(
+    days: Option<u32>,
+    db: State<'_, DatabaseService>,
+) -> Result<UsageMetrics, String> {
+    metrics::summary(&db, days)
+}
+
+/**
+ * Delete the collected usage counters
+ */
+#[tauri::command]
+pub fn clear_usage_metrics(db: State<'_, DatabaseService>) -> Result<bool, String> {
+    db.clear_usage_counters().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Configured read-only shared libraries and whether they are attached
+ */
+#[tauri::command]
+pub fn get_shared_libraries(
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<SharedLibraryStatus>, String> {
+    shared_library::list(&db)
+}
+
+/**
+ * Attach a copyclip database read-only (e.g. a team file synced through Dropbox)
+ * Its items appear in get_clipboard_items with include_shared
+ */
+#[tauri::command]
+pub fn add_shared_library(
+    name: String,
+    path: String,
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<SharedLibraryStatus>, String> {
+    shared_library::add(&db, &name, &path)
+}
+
+/**
+ * Detach a shared library and stop attaching it on startup
+ */
+#[tauri::command]
+pub fn remove_shared_library(
+    name: String,
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<SharedLibraryStatus>, String> {
+    shared_library::remove(&db, &name)
+}
+
+/**
+ * Fuzzy search over launcher aliases and installed applications
+ */
+#[tauri::command]
+pub fn search_apps(
+    query: String,
+    limit: Option<usize>,
+    db: State<'_, DatabaseService>,
+    launcher: State<'_, AppLauncher>,
+) -> Result<Vec<AppMatch>, String> {
+    launcher.search(&db, &query, limit.unwrap_or(20))
+}
+
+/**
+ * Launch an application by alias or by a target from search_apps
+ * Returns the launched target
+ */
+#[tauri::command]
+pub fn launch_app(
+    target: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    launcher: State<'_, AppLauncher>,
+) -> Result<String, String> {
+    let started = Instant::now();
+    let launched =
+        action_feedback::report(&app, "Launch app", launcher.launch(&app, &db, &target))?;
+    metrics::record(&db, Metric::Action("launch_app"), Some(started.elapsed()));
+    Ok(launched)
+}
+
+/**
+ * Get the app launcher aliases
+ */
+#[tauri::command]
+pub fn get_app_aliases(db: State<'_, DatabaseService>) -> Result<Vec<AppAliasModel>, String> {
+    db.get_app_aliases().map_err(|e| e.to_string())
+}
+
+/**
+ * Add an alias for an application, or point an existing alias elsewhere
+ */
+#[tauri::command]
+pub fn set_app_alias(
+    alias: String,
+    target: String,
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<AppAliasModel>, String> {
+    let (alias, target) = (alias.trim(), target.trim());
+    if alias.is_empty() || target.is_empty() {
+        return Err("Alias and target are required".to_string());
+    }
+    db.set_app_alias(alias, target).map_err(|e| e.to_string())?;
+    db.get_app_aliases().map_err(|e| e.to_string())
+}
+
+/**
+ * Remove an app launcher alias
+ */
+#[tauri::command]
+pub fn delete_app_alias(
+    alias: String,
+    db: State<'_, DatabaseService>,
+) -> Result<Vec<AppAliasModel>, String> {
+    db.delete_app_alias(&alias).map_err(|e| e.to_string())?;
+    db.get_app_aliases().map_err(|e| e.to_string())
+}
+
+/**
+ * External tools and display server the platform features depend on
+ * Pass refresh after installing a missing tool, otherwise the startup probe is returned
+ */
+#[tauri::command]
+pub fn get_platform_capabilities(
+    refresh: Option<bool>,
+    capabilities: State<'_, Capabilities>,
+) -> PlatformCapabilities {
+    capabilities.get(refresh.unwrap_or(false))
+}
+
+/**
+ * Check every subsystem without changing history or the clipboard
+ * The report names what is broken instead of "nothing works"
+ */
+#[tauri::command]
+pub async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, String> {
+    // Probes spawn nothing, but clipboard reads and PATH scans can block for a moment
+    tauri::async_runtime::spawn_blocking(move || self_test::run(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Paste an item into the app that had focus before copyclip was shown
+ * Copies the item, hides copyclip, refocuses that app and sends the paste keystroke
+ */
+#[tauri::command]
+pub async fn paste_item_and_restore_focus(
+    id: String,
+    app: AppHandle,
+    db: State<'_, DatabaseService>,
+    history_lock: State<'_, HistoryLock>,
+    clipboard_backend: State<'_, ClipboardBackend>,
+    focus_memory: State<'_, FocusMemory>,
+) -> Result<bool, String> {
+    history_lock.check()?;
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    clipboard::write_item(clipboard_backend.provider(), &db, &item, true)?;
+    hide_main_window(app.clone())?;
+
+    // Focus changes and keystrokes wait on the target app, keep them off the main thread
+    let previous = focus_memory.previous();
+    let pasted =
+        tauri::async_runtime::spawn_blocking(move || focus::return_and_paste(previous.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?;
+    action_feedback::report(&app, "Paste and return", pasted)?;
+
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    metrics::record(&db, Metric::Paste, None);
+    Ok(true)
+}
@@ -0,0 +1,89 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/**
+ * How long a pending confirmation token stays valid
+ */
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/**
+ * Operation that can't be undone and needs a second, explicit step
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DestructiveAction {
+    ClearHistory,
+}
+
+/**
+ * Result of a destructive command: either done or waiting for confirm_action
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ConfirmationResult {
+    Pending {
+        token: String,
+        action: DestructiveAction,
+        expires_at: i64, // ms timestamp
+    },
+    Done,
+}
+
+/**
+ * Pending confirmation tokens
+ * A destructive command called without confirm=true only issues a token;
+ * the operation runs when the token is confirmed before it expires
+ */
+pub struct Confirmations {
+    pending: Mutex<HashMap<String, (DestructiveAction, Instant)>>,
+}
+
+impl Confirmations {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /**
+     * Issue a token for `action`
+     */
+    pub fn request(&self, action: DestructiveAction) -> ConfirmationResult {
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut pending = self.pending.lock();
+        pending.retain(|_, (_, issued)| issued.elapsed() < CONFIRM_TIMEOUT);
+        pending.insert(token.clone(), (action, Instant::now()));
+
+        ConfirmationResult::Pending {
+            token,
+            action,
+            expires_at: chrono::Utc::now().timestamp_millis() + CONFIRM_TIMEOUT.as_millis() as i64,
+        }
+    }
+
+    /**
+     * Consume a token, returning the action it confirms
+     */
+    pub fn take(&self, token: &str) -> Result<DestructiveAction, String> {
+        match self.pending.lock().remove(token) {
+            Some((action, issued)) if issued.elapsed() < CONFIRM_TIMEOUT => Ok(action),
+            Some(_) => Err("Confirmation expired".to_string()),
+            None => Err("Unknown confirmation token".to_string()),
+        }
+    }
+
+    /**
+     * Drop a token without running its action
+     */
+    pub fn cancel(&self, token: &str) -> bool {
+        self.pending.lock().remove(token).is_some()
+    }
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
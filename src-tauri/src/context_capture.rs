@@ -0,0 +1,73 @@
+use base64::Engine;
+use tauri::{AppHandle, Manager};
+
+use crate::db::DatabaseService;
+use crate::screenshot;
+use crate::shutdown::Shutdown;
+
+/**
+ * Opt-in: screenshots can contain anything on screen, so this is off by default
+ */
+pub const ENABLED_SETTING: &str = "context_screenshots_enabled";
+
+/**
+ * Longest side of a stored context screenshot, in pixels
+ */
+const MAX_SIZE: u32 = 640;
+
+/**
+ * Grab a small screenshot of the focused window for a just-saved item
+ * Runs in the background so saving is never delayed; failures are only logged
+ */
+pub fn capture_for(app: &AppHandle, item_id: &str) {
+    let Some(db) = app.try_state::<DatabaseService>() else {
+        return;
+    };
+    let enabled = matches!(
+        db.get_setting(ENABLED_SETTING).ok().flatten().as_deref(),
+        Some("true")
+    );
+    if !enabled {
+        return;
+    }
+
+    let item_id = item_id.to_string();
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("context-capture", move |_| {
+        let result = screenshot::capture_active_window().and_then(|png| shrink(&png));
+        let (image_base64, width, height) = match result {
+            Ok(image) => image,
+            Err(e) => {
+                log::warn!("Failed to capture context screenshot: {}", e);
+                return;
+            }
+        };
+
+        if let Some(db) = app.try_state::<DatabaseService>() {
+            if let Err(e) = db.save_context_image(&item_id, &image_base64, width, height) {
+                log::error!("Failed to store context screenshot: {}", e);
+            }
+        }
+    });
+}
+
+/**
+ * Downscale to MAX_SIZE and re-encode as base64 PNG
+ */
+fn shrink(png: &[u8]) -> Result<(String, u32, u32), String> {
+    let image = image::load_from_memory(png)
+        .map_err(|e| format!("Invalid screenshot: {}", e))?
+        .thumbnail(MAX_SIZE, MAX_SIZE);
+
+    let mut out = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut out), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+
+    Ok((
+        base64::engine::general_purpose::STANDARD.encode(out),
+        image.width(),
+        image.height(),
+    ))
+}
@@ -1,9 +1,41 @@
+use base64::Engine;
 use chrono::Utc;
-use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use parking_lot::Mutex;
+use rusqlite::types::Value;
+use rusqlite::{
+    Connection, DatabaseName, OpenFlags, OptionalExtension, Result as SqliteResult,
+    TransactionBehavior,
+};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::models::{ClipboardItemModel, ClipboardQueryFilter};
+use crate::dedup::{Dedup, DedupPolicy, DedupWindow};
+use crate::highlight;
+use crate::image_hash;
+use crate::keys;
+use crate::models::{
+    AppAliasModel, ArchiveFileModel, ArchiveReportModel, AuditLogEntryModel, ClipboardFileModel,
+    ClipboardItemModel, ClipboardItemPageModel, ClipboardItemSummaryModel, ClipboardQueryFilter,
+    ConsolidateReportModel, ItemContextImageModel, ItemShortcutModel, SortBy, SortDirection,
+    StorageBreakdownModel, StorageStatsModel, TimelineBucketModel, TimelineGrouping,
+    UsageCounterModel, WebhookDeliveryModel,
+};
+use crate::query::ItemQuery;
+
+/**
+ * Unpinned items kept in history, older ones are evicted
+ */
+pub const MAX_ITEMS: i64 = 100;
+
+/**
+ * Result of DatabaseService::save_item
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveOutcome {
+    Saved { evicted: usize }, // inserted, `evicted` old items removed
+    Duplicate { existing: String, bumped: bool }, // matched `existing`, nothing inserted
+}
 
 /**
  * Database service for clipboard history
@@ -12,6 +44,17 @@ use crate::models::{ClipboardItemModel, ClipboardQueryFilter};
  */
 pub struct DatabaseService {
     conn: Mutex<Connection>,
+    db_path: PathBuf,
+    libraries: Mutex<Vec<AttachedLibrary>>, // locked after conn
+}
+
+/**
+ * Shared snippet database attached read-only to the connection
+ */
+struct AttachedLibrary {
+    name: String,
+    schema: String, // lib_<n>
+    items: String,  // subquery exposing ITEM_COLUMNS, columns the library lacks are defaulted
 }
 
 impl DatabaseService {
@@ -27,163 +70,115 @@ impl DatabaseService {
         // Open connection
         let conn = Connection::open(&db_path)?;
 
+        // The native messaging host writes to the same file, wait for its lock instead of failing
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
         // Enable foreign keys
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
-        // Create table
-        conn.execute(
-            r#"
-            CREATE TABLE IF NOT EXISTS clipboard_items (
-                id TEXT PRIMARY KEY,
-                content TEXT NOT NULL,
-                item_type TEXT NOT NULL,
-                is_pinned BOOLEAN DEFAULT 0,
-                timestamp INTEGER NOT NULL,
-                image_base64 TEXT,
-                file_paths TEXT,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )
-            "#,
-            [],
-        )?;
-
-        // Create indexes
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp ON clipboard_items(timestamp DESC);",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_is_pinned ON clipboard_items(is_pinned);",
-            [],
-        )?;
+        // Let deleted pages be returned to the OS (existing files switch on the next VACUUM)
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL;")?;
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_item_type ON clipboard_items(item_type);",
-            [],
-        )?;
+        init_schema(&conn)?;
+        canonicalize_shortcuts(&conn)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
+            db_path,
+            libraries: Mutex::new(Vec::new()),
         })
     }
 
-    /**
-     * Create a new clipboard item
-     */
-    pub fn create_item(&self, item: ClipboardItemModel) -> SqliteResult<usize> {
-        eprintln!(
-            "[DB::CREATE] Creating item: id={}, type={}",
-            item.id, item.item_type
-        );
-        let conn = self.conn.lock().unwrap();
-        eprintln!("[DB::CREATE] Database lock acquired");
-
-        let result = conn.execute(
-            r#"
-            INSERT INTO clipboard_items 
-            (id, content, item_type, is_pinned, timestamp, image_base64, file_paths, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-            rusqlite::params![
-                &item.id,
-                &item.content,
-                &item.item_type,
-                item.is_pinned,
-                item.timestamp,
-                &item.image_base64,
-                &item.file_paths,
-                item.created_at,
-                item.updated_at,
-            ],
-        ).map_err(|e| {
-            eprintln!("[DB::CREATE] ERROR inserting item: {}", e);
-            e
-        })?;
-
-        eprintln!("[DB::CREATE] Item inserted successfully, rows: {}", result);
-        Ok(result)
-    }
-
     /**
      * Get item by id
      */
     pub fn get_item(&self, id: &str) -> SqliteResult<Option<ClipboardItemModel>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, content, item_type, is_pinned, timestamp, image_base64, file_paths, created_at, updated_at FROM clipboard_items WHERE id = ?",
-        )?;
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM clipboard_items WHERE id = ?",
+            ITEM_COLUMNS
+        ))?;
 
         let item = stmt
-            .query_row(rusqlite::params![id], |row| {
-                Ok(ClipboardItemModel {
-                    id: row.get(0)?,
-                    content: row.get(1)?,
-                    item_type: row.get(2)?,
-                    is_pinned: row.get(3)?,
-                    timestamp: row.get(4)?,
-                    image_base64: row.get(5)?,
-                    file_paths: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })
+            .query_row(rusqlite::params![id], item_from_row)
             .optional()?;
+        if item.is_some() {
+            return Ok(item);
+        }
+        drop(stmt);
+
+        for library in self.libraries.lock().iter() {
+            let item = conn
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM {} WHERE id = ?",
+                        ITEM_COLUMNS, library.items
+                    ),
+                    rusqlite::params![id],
+                    item_from_row,
+                )
+                .optional()?;
+            if let Some(mut item) = item {
+                item.library = Some(library.name.clone());
+                return Ok(Some(item));
+            }
+        }
+        drop(conn);
 
-        Ok(item)
+        // Archived items stay reachable, e.g. from include_archives search results
+        for path in self.archive_paths() {
+            let archive = open_archive(&path)?;
+            let item = archive
+                .query_row(
+                    &format!("SELECT {} FROM clipboard_items WHERE id = ?", ITEM_COLUMNS),
+                    rusqlite::params![id],
+                    item_from_row,
+                )
+                .optional()?;
+            if item.is_some() {
+                return Ok(item);
+            }
+        }
+
+        Ok(None)
     }
 
     /**
      * Get all items with filtering
      */
     pub fn get_items(&self, filter: ClipboardQueryFilter) -> SqliteResult<Vec<ClipboardItemModel>> {
-        let conn = self.conn.lock().unwrap();
-        let mut query = String::from(
-            "SELECT id, content, item_type, is_pinned, timestamp, image_base64, file_paths, created_at, updated_at FROM clipboard_items WHERE 1=1"
-        );
+        let conn = self.conn.lock();
+        let mut shared = HashMap::new();
+        let source = if filter.include_archives || filter.include_shared {
+            shared = self.load_extra_hits(&conn, &filter)?;
+            "temp.all_items"
+        } else {
+            "clipboard_items"
+        };
 
-        let mut values: Vec<String> = Vec::new();
+        let (query, values) = ItemQuery::select(ITEM_COLUMNS)
+            .from(source)
+            .filter(&filter)
+            .order_by(&sort_clause(filter.sort_by, filter.sort_direction))
+            .limit(filter.limit)
+            .offset(filter.offset)
+            .build();
 
-        if let Some(search) = &filter.search {
-            query.push_str(" AND content LIKE ?");
-            values.push(format!("%{}%", search));
-        }
+        let mut stmt = conn.prepare(&query)?;
 
-        if let Some(item_type) = &filter.item_type {
-            query.push_str(" AND item_type = ?");
-            values.push(item_type.clone());
-        }
+        let mut items = stmt
+            .query_map(rusqlite::params_from_iter(values), item_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
 
-        if let Some(is_pinned) = filter.is_pinned {
-            query.push_str(&format!(
-                " AND is_pinned = {}",
-                if is_pinned { 1 } else { 0 }
-            ));
+        for item in &mut items {
+            item.library = shared.get(&item.id).cloned();
         }
 
-        query.push_str(&format!(
-            " ORDER BY is_pinned DESC, timestamp DESC LIMIT {} OFFSET {}",
-            filter.limit, filter.offset
-        ));
-
-        let mut stmt = conn.prepare(&query)?;
-
-        let items = stmt
-            .query_map(rusqlite::params_from_iter(values), |row| {
-                Ok(ClipboardItemModel {
-                    id: row.get(0)?,
-                    content: row.get(1)?,
-                    item_type: row.get(2)?,
-                    is_pinned: row.get(3)?,
-                    timestamp: row.get(4)?,
-                    image_base64: row.get(5)?,
-                    file_paths: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(search) = &filter.search {
+            for item in &mut items {
+                item.highlight = highlight::highlight(&item.content, search);
+            }
+        }
 
         Ok(items)
     }
@@ -192,7 +187,7 @@ impl DatabaseService {
      * Update item (toggle pin for example)
      */
     pub fn update_item(&self, id: &str, is_pinned: bool) -> SqliteResult<usize> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn.lock();
         let now = Utc::now().timestamp_millis();
 
         conn.execute(
@@ -205,25 +200,25 @@ impl DatabaseService {
      * Delete item by id
      */
     pub fn delete_item(&self, id: &str) -> SqliteResult<usize> {
-        eprintln!("[DB::DELETE] Starting delete operation for id: {}", id);
+        log::debug!("[DB::DELETE] Starting delete operation for id: {}", id);
 
-        let conn = self.conn.lock().unwrap();
-        eprintln!("[DB::DELETE] Acquired database lock");
+        let conn = self.conn.lock();
+        log::debug!("[DB::DELETE] Acquired database lock");
 
         // Check if item exists first
         let mut check_stmt = conn
             .prepare("SELECT id FROM clipboard_items WHERE id = ? LIMIT 1")
             .map_err(|e| {
-                eprintln!("[DB::DELETE] Error preparing check query: {}", e);
+                log::error!("[DB::DELETE] Error preparing check query: {}", e);
                 e
             })?;
 
         let exists = check_stmt.exists(rusqlite::params![id]).map_err(|e| {
-            eprintln!("[DB::DELETE] Error checking if item exists: {}", e);
+            log::error!("[DB::DELETE] Error checking if item exists: {}", e);
             e
         })?;
 
-        eprintln!("[DB::DELETE] Item exists in database: {}", exists);
+        log::debug!("[DB::DELETE] Item exists in database: {}", exists);
         drop(check_stmt);
 
         // Perform the delete
@@ -233,11 +228,11 @@ impl DatabaseService {
                 rusqlite::params![id],
             )
             .map_err(|e| {
-                eprintln!("[DB::DELETE] Error executing delete: {}", e);
+                log::error!("[DB::DELETE] Error executing delete: {}", e);
                 e
             })?;
 
-        eprintln!(
+        log::debug!(
             "[DB::DELETE] Delete query completed. Rows affected: {}",
             result
         );
@@ -248,7 +243,7 @@ impl DatabaseService {
      * Delete all items
      */
     pub fn delete_all(&self) -> SqliteResult<usize> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn.lock();
         conn.execute("DELETE FROM clipboard_items", [])
     }
 
@@ -256,17 +251,15 @@ impl DatabaseService {
      * Get item count
      */
     pub fn count_items(&self) -> SqliteResult<i64> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.conn.lock();
         let mut stmt = conn.prepare("SELECT COUNT(*) FROM clipboard_items")?;
         let count = stmt.query_row([], |row| row.get(0))?;
         Ok(count)
     }
 
-    /**
-     * Delete items older than specified timestamp
-     */
+    // Delete items older than specified timestamp
     // pub fn delete_old_items(&self, before_timestamp: i64) -> SqliteResult<usize> {
-    //     let conn = self.conn.lock().unwrap();
+    //     let conn = self.conn.lock();
     //     conn.execute(
     //         "DELETE FROM clipboard_items WHERE timestamp < ?",
     //         rusqlite::params![before_timestamp],
@@ -274,52 +267,2108 @@ impl DatabaseService {
     // }
 
     /**
-     * Enforce max items limit
+     * Merge text items into a new item, preserving the order of ids
+     */
+    pub fn merge_items(
+        &self,
+        ids: &[String],
+        separator: &str,
+        max_items: i64,
+    ) -> SqliteResult<Option<ClipboardItemModel>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let mut stmt =
+            tx.prepare("SELECT content FROM clipboard_items WHERE id = ? AND item_type = 'text'")?;
+
+        let mut parts: Vec<String> = Vec::new();
+        for id in ids {
+            if let Some(content) = stmt
+                .query_row(rusqlite::params![id], |row| row.get::<_, String>(0))
+                .optional()?
+            {
+                parts.push(content);
+            }
+        }
+        drop(stmt);
+
+        if parts.is_empty() {
+            return Ok(None);
+        }
+
+        let item = ClipboardItemModel::new(
+            uuid::Uuid::new_v4().to_string(),
+            parts.join(separator),
+            "text".to_string(),
+            None,
+            None,
+        );
+
+        insert_item(&tx, &item)?;
+        enforce_max_items_in(&tx, max_items)?;
+        tx.commit()?;
+
+        Ok(Some(item))
+    }
+
+    /**
+     * Append text to the most recent text item
+     * Returns the id of the updated item, or None if there is no text item to append to
+     * An externalized latest item counts as none: its row only holds a preview of the text
+     */
+    pub fn append_to_latest(
+        &self,
+        content: &str,
+        separator: &str,
+        max_items: i64,
+    ) -> SqliteResult<Option<String>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let now = Utc::now().timestamp_millis();
+
+        let latest = tx
+            .query_row(
+                "SELECT id, content, content_file FROM clipboard_items WHERE item_type = 'text' ORDER BY timestamp DESC LIMIT 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((id, existing, None)) = latest else {
+            return Ok(None);
+        };
+
+        // Keep content_hash in step so dedup matches the appended text
+        let appended = format!("{}{}{}", existing, separator, content);
+        tx.execute(
+            "UPDATE clipboard_items SET content = ?, content_hash = ?, timestamp = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![&appended, content_hash("text", &appended, None), now, now, &id],
+        )?;
+        enforce_max_items_in(&tx, max_items)?;
+        tx.commit()?;
+
+        Ok(Some(id))
+    }
+
+    /**
+     * Save a captured item atomically: dedup check, insert or bump, and max items eviction
+     * run in one IMMEDIATE transaction so concurrent captures can't both insert the same copy
+     * Dedup windows allow repeats, so the content hash is indexed rather than UNIQUE
+     */
+    pub fn save_item(
+        &self,
+        item: &ClipboardItemModel,
+        dedup: &Dedup,
+        image_threshold: u32,
+        max_items: i64,
+    ) -> SqliteResult<SaveOutcome> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        // A selection isn't a duplicate of a copy, only items of the same source are compared
+        let scope = SourceScope {
+            source: &item.source,
+            window: &dedup.window,
+        };
+        let duplicate = match (&item.image_hash, &item.image_base64) {
+            (Some(hash), _) => find_duplicate_image_in(&tx, hash, image_threshold, &scope)?,
+            (None, Some(image)) => find_duplicate_image_bytes_in(&tx, image, &scope)?,
+            (None, None) => {
+                let hash =
+                    content_hash(&item.item_type, &item.content, item.content_file.as_deref());
+                find_duplicate_in(&tx, &hash, &scope)?
+            }
+        };
+
+        let outcome = match duplicate {
+            Some(existing) => {
+                let bumped = dedup.policy == DedupPolicy::Bump && bump_item_in(&tx, &existing)? > 0;
+                SaveOutcome::Duplicate { existing, bumped }
+            }
+            None => {
+                insert_item(&tx, item)?;
+                SaveOutcome::Saved {
+                    evicted: enforce_max_items_in(&tx, max_items)?,
+                }
+            }
+        };
+
+        tx.commit()?;
+        Ok(outcome)
+    }
+
+    /**
+     * Get a setting value by key
+     */
+    pub fn get_setting(&self, key: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            rusqlite::params![key],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /**
+     * Remove a setting so its default applies again
+     */
+    pub fn delete_setting(&self, key: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM settings WHERE key = ?", rusqlite::params![key])
+    }
+
+    /**
+     * Get all settings
+     */
+    pub fn get_settings(&self) -> SqliteResult<HashMap<String, String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+        let settings = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(settings)
+    }
+
+    /**
+     * Insert or update a setting
      */
-    pub fn enforce_max_items(&self, max_items: i64) -> SqliteResult<usize> {
-        let conn = self.conn.lock().unwrap();
+    pub fn set_setting(&self, key: &str, value: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        let now = Utc::now().timestamp_millis();
+
         conn.execute(
             r#"
-            DELETE FROM clipboard_items WHERE id IN (
-                SELECT id FROM clipboard_items 
-                WHERE is_pinned = 0
-                ORDER BY timestamp ASC 
-                LIMIT MAX(0, (SELECT COUNT(*) - ? FROM clipboard_items WHERE is_pinned = 0))
-            )
+            INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
             "#,
-            rusqlite::params![max_items],
+            rusqlite::params![key, value, now],
         )
     }
 
     /**
-     * Check if item with same content exists (for deduplication)
+     * Replace the file rows of an item
      */
-    pub fn check_duplicate(&self, content: &str, item_type: &str) -> SqliteResult<bool> {
-        eprintln!(
-            "[DB::CHECK_DUP] Checking duplicate: type={}, content_len={}",
-            item_type,
-            content.len()
-        );
-        let conn = self.conn.lock().unwrap();
-        eprintln!("[DB::CHECK_DUP] Database lock acquired");
+    pub fn save_item_files(
+        &self,
+        item_id: &str,
+        files: &[ClipboardFileModel],
+    ) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM clipboard_files WHERE item_id = ?",
+            rusqlite::params![item_id],
+        )?;
+
+        for file in files {
+            tx.execute(
+                "INSERT INTO clipboard_files (item_id, path, size, mime, file_exists) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![item_id, &file.path, file.size, &file.mime, file.exists],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(files.len())
+    }
 
+    /**
+     * Get the file rows of an item
+     */
+    pub fn get_item_files(&self, item_id: &str) -> SqliteResult<Vec<ClipboardFileModel>> {
+        let conn = self.conn.lock();
         let mut stmt = conn.prepare(
-            "SELECT COUNT(*) FROM clipboard_items WHERE content = ? AND item_type = ? ORDER BY timestamp DESC LIMIT 1"
-        ).map_err(|e| {
-            eprintln!("[DB::CHECK_DUP] ERROR preparing query: {}", e);
-            e
-        })?;
+            "SELECT item_id, path, size, mime, file_exists FROM clipboard_files WHERE item_id = ? ORDER BY id",
+        )?;
 
-        let count = stmt
-            .query_row(rusqlite::params![content, item_type], |row| {
-                row.get::<_, i64>(0)
-            })
-            .map_err(|e| {
-                eprintln!("[DB::CHECK_DUP] ERROR querying: {}", e);
-                e
-            })?;
+        let files = stmt
+            .query_map(rusqlite::params![item_id], |row| {
+                Ok(ClipboardFileModel {
+                    item_id: row.get(0)?,
+                    path: row.get(1)?,
+                    size: row.get(2)?,
+                    mime: row.get(3)?,
+                    exists: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(files)
+    }
+
+    /**
+     * Count items per local day or week for timeline navigation
+     */
+    pub fn get_timeline(
+        &self,
+        grouping: TimelineGrouping,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> SqliteResult<Vec<TimelineBucketModel>> {
+        let conn = self.conn.lock();
+        let format = match grouping {
+            TimelineGrouping::Day => "%Y-%m-%d",
+            TimelineGrouping::Week => "%Y-W%W",
+        };
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT strftime(?1, timestamp / 1000, 'unixepoch', 'localtime') AS period,
+                   COUNT(*), MIN(timestamp), MAX(timestamp)
+            FROM clipboard_items
+            WHERE (?2 IS NULL OR timestamp >= ?2) AND (?3 IS NULL OR timestamp < ?3)
+            GROUP BY period
+            ORDER BY period DESC
+            "#,
+        )?;
+
+        let buckets = stmt
+            .query_map(rusqlite::params![format, since, until], |row| {
+                Ok(TimelineBucketModel {
+                    period: row.get(0)?,
+                    count: row.get(1)?,
+                    first_timestamp: row.get(2)?,
+                    last_timestamp: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(buckets)
+    }
+
+    /**
+     * Bind a shortcut to an item, replacing any previous binding of that shortcut
+     */
+    pub fn bind_shortcut(&self, shortcut: &str, item_id: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        let now = Utc::now().timestamp_millis();
+
+        conn.execute(
+            r#"
+            INSERT INTO item_shortcuts (shortcut, item_id, created_at) VALUES (?, ?, ?)
+            ON CONFLICT(shortcut) DO UPDATE SET item_id = excluded.item_id, created_at = excluded.created_at
+            "#,
+            rusqlite::params![shortcut, item_id, now],
+        )
+    }
+
+    /**
+     * Remove a shortcut binding
+     */
+    pub fn unbind_shortcut(&self, shortcut: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM item_shortcuts WHERE shortcut = ?",
+            rusqlite::params![shortcut],
+        )
+    }
+
+    /**
+     * Remove every shortcut bound to an item, e.g. when it is unpinned
+     */
+    pub fn unbind_item_shortcuts(&self, item_id: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM item_shortcuts WHERE item_id = ?",
+            rusqlite::params![item_id],
+        )
+    }
+
+    /**
+     * Get all shortcut bindings
+     */
+    pub fn get_shortcuts(&self) -> SqliteResult<Vec<ItemShortcutModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT shortcut, item_id, created_at FROM item_shortcuts ORDER BY shortcut",
+        )?;
+
+        let shortcuts = stmt
+            .query_map([], |row| {
+                Ok(ItemShortcutModel {
+                    shortcut: row.get(0)?,
+                    item_id: row.get(1)?,
+                    created_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(shortcuts)
+    }
+
+    /**
+     * Resolve the item bound to a shortcut
+     */
+    pub fn get_shortcut_item(&self, shortcut: &str) -> SqliteResult<Option<ClipboardItemModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM clipboard_items WHERE id = (SELECT item_id FROM item_shortcuts WHERE shortcut = ?)",
+            ITEM_COLUMNS
+        ))?;
+
+        stmt.query_row(rusqlite::params![shortcut], item_from_row)
+            .optional()
+    }
+
+    /**
+     * Add or retarget an app launcher alias
+     */
+    pub fn set_app_alias(&self, alias: &str, target: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        let now = Utc::now().timestamp_millis();
+
+        conn.execute(
+            r#"
+            INSERT INTO app_aliases (alias, target, created_at) VALUES (?, ?, ?)
+            ON CONFLICT(alias) DO UPDATE SET target = excluded.target
+            "#,
+            rusqlite::params![alias, target, now],
+        )
+    }
+
+    /**
+     * Remove an app launcher alias, returns the rows deleted
+     */
+    pub fn delete_app_alias(&self, alias: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM app_aliases WHERE alias = ?",
+            rusqlite::params![alias],
+        )
+    }
+
+    /**
+     * Get all app launcher aliases
+     */
+    pub fn get_app_aliases(&self) -> SqliteResult<Vec<AppAliasModel>> {
+        let conn = self.conn.lock();
+        let mut stmt =
+            conn.prepare("SELECT alias, target, created_at FROM app_aliases ORDER BY alias")?;
+
+        let aliases = stmt
+            .query_map([], |row| {
+                Ok(AppAliasModel {
+                    alias: row.get(0)?,
+                    target: row.get(1)?,
+                    created_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(aliases)
+    }
+
+    /**
+     * Target of an alias (case-insensitive)
+     */
+    pub fn get_app_alias_target(&self, alias: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT target FROM app_aliases WHERE alias = ?",
+            rusqlite::params![alias],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /**
+     * Record a destructive operation in the audit log
+     */
+    pub fn record_audit(
+        &self,
+        operation: &str,
+        detail: Option<&str>,
+        affected: usize,
+    ) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        let now = Utc::now().timestamp_millis();
+
+        conn.execute(
+            "INSERT INTO audit_log (operation, detail, affected, created_at) VALUES (?, ?, ?, ?)",
+            rusqlite::params![operation, detail, affected as i64, now],
+        )
+    }
+
+    /**
+     * Get the most recent audit log entries
+     */
+    pub fn get_audit_log(&self, limit: u64) -> SqliteResult<Vec<AuditLogEntryModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, operation, detail, affected, created_at FROM audit_log ORDER BY created_at DESC, id DESC LIMIT ?",
+        )?;
+
+        let entries = stmt
+            .query_map(rusqlite::params![limit as i64], |row| {
+                Ok(AuditLogEntryModel {
+                    id: row.get(0)?,
+                    operation: row.get(1)?,
+                    detail: row.get(2)?,
+                    affected: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /**
+     * Add one occurrence (and its latency) to a day's usage counter
+     */
+    pub fn record_usage(&self, day: &str, metric: &str, elapsed_ms: i64) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO usage_metrics (day, metric, count, total_ms) VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(day, metric) DO UPDATE SET count = count + 1, total_ms = total_ms + ?3",
+            rusqlite::params![day, metric, elapsed_ms],
+        )
+    }
+
+    /**
+     * Usage counters from `since_day` (YYYY-MM-DD) on, oldest first
+     */
+    pub fn get_usage_counters(&self, since_day: &str) -> SqliteResult<Vec<UsageCounterModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT day, metric, count, total_ms FROM usage_metrics WHERE day >= ? ORDER BY day, metric",
+        )?;
+
+        let counters = stmt
+            .query_map(rusqlite::params![since_day], |row| {
+                Ok(UsageCounterModel {
+                    day: row.get(0)?,
+                    metric: row.get(1)?,
+                    count: row.get(2)?,
+                    total_ms: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(counters)
+    }
+
+    /**
+     * Delete all usage counters
+     */
+    pub fn clear_usage_counters(&self) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM usage_metrics", [])
+    }
+
+    /**
+     * Path of the database file
+     */
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /**
+     * Copy the live database to `dest` using SQLite's online backup API
+     */
+    pub fn backup_to(&self, dest: &Path) -> SqliteResult<()> {
+        let conn = self.conn.lock();
+        conn.backup(DatabaseName::Main, dest, None)
+    }
+
+    /**
+     * Replace the live database with the contents of `src`
+     * The source is validated first; the caller should snapshot beforehand
+     */
+    pub fn restore_from(&self, src: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        validate_backup(src)?;
+
+        let mut conn = self.conn.lock();
+        conn.restore(
+            DatabaseName::Main,
+            src,
+            None::<fn(rusqlite::backup::Progress)>,
+        )?;
+
+        // Backups from older versions may predate newer tables/columns
+        init_schema(&conn)?;
+        Ok(())
+    }
+
+    /**
+     * Run PRAGMA quick_check (or integrity_check when `full`)
+     * Returns the reported problems, empty when the database is healthy
+     */
+    pub fn check_integrity(&self, full: bool) -> SqliteResult<Vec<String>> {
+        let conn = self.conn.lock();
+        let pragma = if full {
+            "PRAGMA integrity_check"
+        } else {
+            "PRAGMA quick_check"
+        };
+
+        let mut stmt = conn.prepare(pragma)?;
+        let messages = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(messages.into_iter().filter(|m| m != "ok").collect())
+    }
+
+    /**
+     * Write a throwaway settings row, read it back and roll back
+     * Proves the database accepts writes without leaving anything behind
+     */
+    pub fn check_round_trip(&self) -> SqliteResult<bool> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let token = uuid::Uuid::new_v4().to_string();
+
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES ('self_test', ?, ?)",
+            rusqlite::params![&token, Utc::now().timestamp_millis()],
+        )?;
+        let read: String = tx.query_row(
+            "SELECT value FROM settings WHERE key = 'self_test'",
+            [],
+            |row| row.get(0),
+        )?;
+
+        tx.rollback()?;
+        Ok(read == token)
+    }
+
+    /**
+     * Run a closure with the locked connection, for maintenance tasks
+     */
+    pub fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> SqliteResult<T>,
+    ) -> SqliteResult<T> {
+        let conn = self.conn.lock();
+        f(&conn)
+    }
+
+    /**
+     * Page usage of the database file
+     */
+    pub fn storage_stats(&self) -> SqliteResult<StorageStatsModel> {
+        let conn = self.conn.lock();
+        let pragma = |name: &str| {
+            conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0))
+        };
+
+        let page_size = pragma("page_size")?;
+        let page_count = pragma("page_count")?;
+        let freelist_count = pragma("freelist_count")?;
+
+        Ok(StorageStatsModel {
+            page_size,
+            page_count,
+            freelist_count,
+            size_bytes: page_size * page_count,
+            free_bytes: page_size * freelist_count,
+        })
+    }
+
+    /**
+     * Rebuild the database file, reclaiming all free pages
+     */
+    pub fn vacuum(&self) -> SqliteResult<()> {
+        let conn = self.conn.lock();
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")
+    }
+
+    /**
+     * Release free pages without a full rebuild (needs auto_vacuum = INCREMENTAL)
+     */
+    pub fn incremental_vacuum(&self) -> SqliteResult<()> {
+        let conn = self.conn.lock();
+        conn.execute_batch("PRAGMA incremental_vacuum;")
+    }
+
+    /**
+     * Get a page of item summaries using keyset pagination
+     * `cursor` is the opaque next_cursor from the previous page
+     * Fails on a malformed cursor, or a stale one whose item was pinned or bumped since
+     */
+    pub fn get_items_page(
+        &self,
+        filter: &ClipboardQueryFilter,
+        cursor: Option<&str>,
+    ) -> SqliteResult<ClipboardItemPageModel> {
+        let conn = self.conn.lock();
+        let mut columns = summary_columns();
+        let mut column_values = Vec::new();
+
+        // Cut the highlight window in SQL so huge contents never leave the database
+        if let Some(search) = &filter.search {
+            columns.push_str(&format!(
+                ", max(instr(lower(content), lower(?)) - {before}, 1) AS snippet_start, substr(content, max(instr(lower(content), lower(?)) - {before}, 1), {len})",
+                before = highlight::CONTEXT_BEFORE,
+                len = highlight::SNIPPET_CHARS
+            ));
+            column_values = vec![Value::Text(search.clone()), Value::Text(search.clone())];
+        }
+
+        let mut query = ItemQuery::select(&columns)
+            .column_values(column_values)
+            .filter(filter);
+
+        if let Some(cursor) = cursor {
+            let (pinned, timestamp, id) = decode_cursor(cursor).ok_or_else(|| {
+                rusqlite::Error::InvalidParameterName("invalid cursor".to_string())
+            })?;
+
+            // A pinned or bumped item moved, continuing after it would repeat or skip rows
+            let moved = conn
+                .query_row(
+                    "SELECT is_pinned != ? OR timestamp != ? FROM clipboard_items WHERE id = ?",
+                    rusqlite::params![pinned, timestamp, id],
+                    |row| row.get::<_, bool>(0),
+                )
+                .optional()?
+                .unwrap_or(false);
+            if moved {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "stale cursor".to_string(),
+                ));
+            }
+
+            query = query.and_where(
+                "is_pinned < ? OR (is_pinned = ? AND (timestamp < ? OR (timestamp = ? AND id < ?)))",
+                [
+                    Value::Integer(pinned),
+                    Value::Integer(pinned),
+                    Value::Integer(timestamp),
+                    Value::Integer(timestamp),
+                    Value::Text(id),
+                ],
+            );
+        }
+
+        // Fetch one extra row to know whether another page exists
+        let (query, values) = query
+            .order_by("is_pinned DESC, timestamp DESC, id DESC")
+            .limit(filter.limit.saturating_add(1))
+            .build();
+
+        let mut stmt = conn.prepare(&query)?;
+        let mut items = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                let mut summary = summary_from_row(row)?;
+                if let Some(search) = &filter.search {
+                    let start: i64 = row.get(12)?;
+                    let snippet: String = row.get(13)?;
+                    let end = start - 1 + snippet.chars().count() as i64;
+                    summary.highlight = Some(highlight::build(
+                        &snippet,
+                        search,
+                        start > 1,
+                        end < summary.content_length,
+                    ));
+                }
+                Ok(summary)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next_cursor = if items.len() as u64 > filter.limit {
+            items.truncate(filter.limit as usize);
+            items.last().map(encode_cursor)
+        } else {
+            None
+        };
+
+        Ok(ClipboardItemPageModel { items, next_cursor })
+    }
+
+    /**
+     * Count a paste of the item back from history (drives the "most used" sort)
+     */
+    pub fn record_use(&self, id: &str) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE clipboard_items SET use_count = use_count + 1 WHERE id = ?",
+            [id],
+        )
+    }
+
+    /**
+     * Get history disk usage grouped by item type, largest first
+     */
+    pub fn get_storage_breakdown(&self) -> SqliteResult<Vec<StorageBreakdownModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT item_type, COUNT(*), SUM(is_pinned), SUM({text}), SUM({image}) FROM clipboard_items GROUP BY item_type ORDER BY SUM({text}) + SUM({image}) DESC",
+            text = TEXT_BYTES_SQL,
+            image = IMAGE_BYTES_SQL
+        ))?;
+
+        let breakdown = stmt
+            .query_map([], |row| {
+                let text_bytes: i64 = row.get(3)?;
+                let image_bytes: i64 = row.get(4)?;
+                Ok(StorageBreakdownModel {
+                    item_type: row.get(0)?,
+                    item_count: row.get(1)?,
+                    pinned_count: row.get(2)?,
+                    text_bytes,
+                    image_bytes,
+                    total_bytes: text_bytes + image_bytes,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(breakdown)
+    }
+
+    /**
+     * Delete the oldest unpinned items until history fits in `budget_bytes`
+     * Pinned items are never evicted, so the result may stay over budget
+     */
+    pub fn evict_to_budget(&self, budget_bytes: i64) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock();
+        let size_sql = format!("{} + {}", TEXT_BYTES_SQL, IMAGE_BYTES_SQL);
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COALESCE(SUM({}), 0) FROM clipboard_items", size_sql),
+            [],
+            |row| row.get(0),
+        )?;
+        if total <= budget_bytes {
+            return Ok(0);
+        }
+
+        let tx = conn.transaction()?;
+        let mut excess = total - budget_bytes;
+        let mut evicted = 0;
+        {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT id, {} FROM clipboard_items WHERE is_pinned = 0 ORDER BY timestamp ASC",
+                size_sql
+            ))?;
+            let mut rows = stmt.query([])?;
+            while excess > 0 {
+                let Some(row) = rows.next()? else {
+                    break;
+                };
+                let id: String = row.get(0)?;
+                let size: i64 = row.get(1)?;
+                tx.execute("DELETE FROM clipboard_items WHERE id = ?", [&id])?;
+                excess -= size;
+                evicted += 1;
+            }
+        }
+        tx.commit()?;
+
+        Ok(evicted)
+    }
+
+    /**
+     * SQLite data_version; changes when another connection commits to the file
+     */
+    pub fn data_version(&self) -> SqliteResult<i64> {
+        let conn = self.conn.lock();
+        conn.query_row("PRAGMA data_version", [], |row| row.get(0))
+    }
+
+    /**
+     * Record the final outcome of a webhook delivery
+     */
+    pub fn record_webhook_delivery(
+        &self,
+        item_id: &str,
+        url: &str,
+        attempts: u32,
+        result: &Result<u16, String>,
+    ) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        let now = Utc::now().timestamp_millis();
+        let (http_status, error) = match result {
+            Ok(status) => (Some(i64::from(*status)), None),
+            Err(e) => (None, Some(e.as_str())),
+        };
+
+        conn.execute(
+            "INSERT INTO webhook_deliveries (item_id, url, delivered, attempts, http_status, error, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![item_id, url, result.is_ok(), attempts, http_status, error, now],
+        )
+    }
+
+    /**
+     * Get the most recent webhook deliveries
+     */
+    pub fn get_webhook_deliveries(&self, limit: u64) -> SqliteResult<Vec<WebhookDeliveryModel>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, item_id, url, delivered, attempts, http_status, error, created_at FROM webhook_deliveries ORDER BY created_at DESC, id DESC LIMIT ?",
+        )?;
+
+        let deliveries = stmt
+            .query_map(rusqlite::params![limit as i64], |row| {
+                Ok(WebhookDeliveryModel {
+                    id: row.get(0)?,
+                    item_id: row.get(1)?,
+                    url: row.get(2)?,
+                    delivered: row.get(3)?,
+                    attempts: row.get(4)?,
+                    http_status: row.get(5)?,
+                    error: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(deliveries)
+    }
+
+    /**
+     * Merge near-duplicate items (same text ignoring whitespace, same image hash)
+     * Pinned items are never merged away; unpinned duplicates are folded into the
+     * first pinned (or newest) item of the group, which inherits their use counts and shortcuts
+     */
+    pub fn consolidate_duplicates(&self) -> SqliteResult<ConsolidateReportModel> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+
+        let mut groups: HashMap<(String, String), Vec<GroupedItem>> = HashMap::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT id, item_type, content, image_hash, use_count, content_file, is_pinned FROM clipboard_items WHERE image_base64 IS NULL OR image_hash IS NOT NULL ORDER BY is_pinned DESC, timestamp DESC",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let id: String = row.get(0)?;
+                let item_type: String = row.get(1)?;
+                let image_hash: Option<String> = row.get(3)?;
+                let content_file: Option<String> = row.get(5)?;
+                let key = match (image_hash, content_file) {
+                    (Some(hash), _) => ("image".to_string(), hash),
+                    // content is only a preview here; the file is named by the full text's hash
+                    (None, Some(file)) => (item_type, format!("file:{}", file)),
+                    (None, None) => (item_type, normalized_hash(&row.get::<_, String>(2)?)),
+                };
+                groups.entry(key).or_default().push(GroupedItem {
+                    id,
+                    use_count: row.get(4)?,
+                    is_pinned: row.get(6)?,
+                });
+            }
+        }
+
+        let mut report = ConsolidateReportModel {
+            groups: 0,
+            merged: 0,
+        };
+        for items in groups.values() {
+            // Pinned items come first and are all kept, only unpinned copies are merged away
+            let keep = &items[0];
+            let merged: Vec<_> = items[1..].iter().filter(|item| !item.is_pinned).collect();
+            if merged.is_empty() {
+                continue;
+            }
+            let use_count = keep.use_count + merged.iter().map(|item| item.use_count).sum::<i64>();
+
+            for item in &merged {
+                tx.execute(
+                    "UPDATE OR IGNORE item_shortcuts SET item_id = ? WHERE item_id = ?",
+                    rusqlite::params![keep.id, item.id],
+                )?;
+                tx.execute("DELETE FROM clipboard_items WHERE id = ?", [&item.id])?;
+            }
+            tx.execute(
+                "UPDATE clipboard_items SET use_count = ? WHERE id = ?",
+                rusqlite::params![use_count, keep.id],
+            )?;
+
+            report.groups += 1;
+            report.merged += merged.len();
+        }
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    /**
+     * Directory holding the monthly archive databases
+     */
+    pub fn archive_dir(&self) -> PathBuf {
+        self.db_path
+            .parent()
+            .map(|dir| dir.join("archives"))
+            .unwrap_or_else(|| PathBuf::from("archives"))
+    }
+
+    /**
+     * Folder holding the full text of externalized large items
+     */
+    pub fn large_content_dir(&self) -> PathBuf {
+        self.db_path
+            .parent()
+            .map(|dir| dir.join("large_items"))
+            .unwrap_or_else(|| PathBuf::from("large_items"))
+    }
+
+    /**
+     * Large item files referenced from history or any archive
+     */
+    pub fn referenced_content_files(&self) -> SqliteResult<HashSet<String>> {
+        let mut files = HashSet::new();
+        let sql = "SELECT content_file FROM clipboard_items WHERE content_file IS NOT NULL";
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(sql)?;
+        for file in stmt.query_map([], |row| row.get(0))? {
+            files.insert(file?);
+        }
+
+        for path in self.archive_paths() {
+            let archive = open_archive(&path)?;
+            let mut stmt = archive.prepare(sql)?;
+            for file in stmt.query_map([], |row| row.get(0))? {
+                files.insert(file?);
+            }
+        }
+        Ok(files)
+    }
+
+    /**
+     * Archive files, newest month first
+     */
+    fn archive_paths(&self) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(self.archive_dir()) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| archive_month(path).is_some())
+            .collect();
+        paths.sort();
+        paths.reverse();
+        paths
+    }
+
+    /**
+     * List the monthly archive files
+     */
+    pub fn list_archives(&self) -> Vec<ArchiveFileModel> {
+        self.archive_paths()
+            .into_iter()
+            .filter_map(|path| {
+                Some(ArchiveFileModel {
+                    month: archive_month(&path)?.to_string(),
+                    size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                    path: path.display().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /**
+     * Attach a copyclip database read-only as the shared library `name`
+     * SQLite rejects any write to it, its items show up in include_shared queries
+     */
+    pub fn attach_library(
+        &self,
+        name: &str,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.conn.lock();
+        let mut libraries = self.libraries.lock();
+        if libraries.iter().any(|library| library.name == name) {
+            return Err(format!("Shared library '{}' is already attached", name).into());
+        }
+
+        let schema = (0..)
+            .map(|n| format!("lib_{}", n))
+            .find(|schema| !libraries.iter().any(|library| &library.schema == schema))
+            .unwrap_or_default();
+        conn.execute(
+            &format!("ATTACH DATABASE ? AS {}", schema),
+            rusqlite::params![read_only_uri(path)],
+        )?;
+
+        // Older libraries may lack newer columns, default them instead of failing every query
+        let existing: HashSet<String> = conn
+            .prepare("SELECT name FROM pragma_table_info('clipboard_items', ?)")?
+            .query_map(rusqlite::params![schema], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        if existing.is_empty() {
+            conn.execute(&format!("DETACH DATABASE {}", schema), [])?;
+            return Err(format!("{} is not a copyclip database", path.display()).into());
+        }
+
+        let columns: Vec<String> = ITEM_COLUMNS
+            .split(", ")
+            .map(|column| match column {
+                _ if existing.contains(column) => column.to_string(),
+                "use_count" => "0 AS use_count".to_string(),
+                "source" => "'clipboard' AS source".to_string(),
+                _ => format!("NULL AS {}", column),
+            })
+            .collect();
+
+        libraries.push(AttachedLibrary {
+            name: name.to_string(),
+            items: format!(
+                "(SELECT {} FROM {}.clipboard_items)",
+                columns.join(", "),
+                schema
+            ),
+            schema,
+        });
+        Ok(())
+    }
+
+    /**
+     * Detach a shared library, returns false when it wasn't attached
+     */
+    pub fn detach_library(&self, name: &str) -> SqliteResult<bool> {
+        let conn = self.conn.lock();
+        let mut libraries = self.libraries.lock();
+        let Some(index) = libraries.iter().position(|library| library.name == name) else {
+            return Ok(false);
+        };
+
+        conn.execute(&format!("DETACH DATABASE {}", libraries[index].schema), [])?;
+        libraries.remove(index);
+        Ok(true)
+    }
+
+    /**
+     * Item count of each attached shared library, by name
+     */
+    pub fn library_item_counts(&self) -> SqliteResult<HashMap<String, i64>> {
+        let conn = self.conn.lock();
+        self.libraries
+            .lock()
+            .iter()
+            .map(|library| {
+                let count = conn.query_row(
+                    &format!("SELECT COUNT(*) FROM {}.clipboard_items", library.schema),
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok((library.name.clone(), count))
+            })
+            .collect()
+    }
+
+    /**
+     * Shared library holding the item, None when it is in the user's own history
+     */
+    pub fn library_of(&self, id: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock();
+        let own = conn
+            .prepare("SELECT 1 FROM main.clipboard_items WHERE id = ?")?
+            .exists(rusqlite::params![id])?;
+        if own {
+            return Ok(None);
+        }
+
+        for library in self.libraries.lock().iter() {
+            let found = conn
+                .prepare(&format!(
+                    "SELECT 1 FROM {}.clipboard_items WHERE id = ?",
+                    library.schema
+                ))?
+                .exists(rusqlite::params![id])?;
+            if found {
+                return Ok(Some(library.name.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /**
+     * Move unpinned items older than `cutoff` (ms) into monthly archive files
+     * Items with a bound shortcut stay in the main database
+     */
+    pub fn archive_items_before(
+        &self,
+        cutoff: i64,
+    ) -> Result<ArchiveReportModel, Box<dyn std::error::Error>> {
+        let archive_dir = self.archive_dir();
+        let mut conn = self.conn.lock();
+
+        let months: Vec<String> = conn
+            .prepare(&format!(
+                "SELECT DISTINCT {} FROM clipboard_items WHERE {} ORDER BY 1",
+                MONTH_SQL, ARCHIVABLE_SQL
+            ))?
+            .query_map([cutoff], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        let mut report = ArchiveReportModel {
+            archived: 0,
+            months: Vec::new(),
+        };
+        if months.is_empty() {
+            return Ok(report);
+        }
+
+        std::fs::create_dir_all(&archive_dir)?;
+
+        for month in months {
+            let path = archive_dir.join(format!("copyclip-archive-{}.db", month));
+            // Creates the file with the current schema (or migrates an older one)
+            drop(open_archive(&path)?);
+
+            conn.execute(
+                "ATTACH DATABASE ? AS archive",
+                rusqlite::params![path.to_string_lossy()],
+            )?;
+            let moved = move_month_to_archive(&mut conn, &month, cutoff);
+            conn.execute("DETACH DATABASE archive", [])?;
+
+            report.archived += moved?;
+            report.months.push(month);
+        }
+
+        Ok(report)
+    }
+
+    /**
+     * Fill temp.archive_hits with the best matches for the filter from each archive
+     * (include_archives) and shared library (include_shared)
+     * Any row in the global first offset+limit is in its own source's first offset+limit
+     * Returns the shared library of each library hit
+     */
+    fn load_extra_hits(
+        &self,
+        conn: &Connection,
+        filter: &ClipboardQueryFilter,
+    ) -> SqliteResult<HashMap<String, String>> {
+        conn.execute_batch(
+            r#"
+            CREATE TEMP TABLE IF NOT EXISTS archive_hits AS SELECT * FROM main.clipboard_items WHERE 0;
+            CREATE TEMP VIEW IF NOT EXISTS all_items AS
+                SELECT * FROM main.clipboard_items UNION ALL SELECT * FROM temp.archive_hits;
+            DELETE FROM temp.archive_hits;
+            "#,
+        )?;
+
+        let hits = |source: &str| {
+            ItemQuery::select(ITEM_COLUMNS)
+                .from(source)
+                .filter(filter)
+                .order_by(&sort_clause(filter.sort_by, filter.sort_direction))
+                .limit(filter.limit.saturating_add(filter.offset))
+                .build()
+        };
+
+        let archives = match filter.include_archives {
+            true => self.archive_paths(),
+            false => Vec::new(),
+        };
+        for path in archives {
+            let archive = open_archive(&path)?;
+            let (query, values) = hits("clipboard_items");
+
+            let mut stmt = archive.prepare(&query)?;
+            let items = stmt.query_map(rusqlite::params_from_iter(values), item_from_row)?;
+            for item in items {
+                insert_item_into(conn, "temp.archive_hits", &item?)?;
+            }
+        }
+
+        let mut shared = HashMap::new();
+        if filter.include_shared {
+            for library in self.libraries.lock().iter() {
+                let (query, values) = hits(&library.items);
+
+                let mut stmt = conn.prepare(&query)?;
+                let items = stmt.query_map(rusqlite::params_from_iter(values), item_from_row)?;
+                for item in items {
+                    let item = item?;
+                    insert_item_into(conn, "temp.archive_hits", &item)?;
+                    shared.insert(item.id, library.name.clone());
+                }
+            }
+        }
+
+        Ok(shared)
+    }
+
+    /**
+     * Store (or replace) the context screenshot of an item
+     */
+    pub fn save_context_image(
+        &self,
+        item_id: &str,
+        image_base64: &str,
+        width: u32,
+        height: u32,
+    ) -> SqliteResult<usize> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT OR REPLACE INTO item_context_images (item_id, image_base64, width, height, captured_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![
+                item_id,
+                image_base64,
+                width,
+                height,
+                chrono::Utc::now().timestamp_millis()
+            ],
+        )
+    }
+
+    /**
+     * Get the context screenshot of an item, loaded separately from the item itself
+     */
+    pub fn get_context_image(&self, item_id: &str) -> SqliteResult<Option<ItemContextImageModel>> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT item_id, image_base64, width, height, captured_at FROM item_context_images WHERE item_id = ?",
+            rusqlite::params![item_id],
+            |row| {
+                Ok(ItemContextImageModel {
+                    item_id: row.get(0)?,
+                    image_base64: row.get(1)?,
+                    width: row.get(2)?,
+                    height: row.get(3)?,
+                    captured_at: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /**
+     * Summaries for the given ids, in the order of `ids`; missing ids are skipped
+     */
+    pub fn get_item_summaries(
+        &self,
+        ids: &[String],
+    ) -> SqliteResult<Vec<ClipboardItemSummaryModel>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock();
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let (query, values) = ItemQuery::select(&summary_columns())
+            .and_where(
+                &format!("id IN ({})", placeholders),
+                ids.iter().map(|id| Value::Text(id.clone())),
+            )
+            .build();
+
+        let mut stmt = conn.prepare(&query)?;
+        let mut summaries: HashMap<String, ClipboardItemSummaryModel> = stmt
+            .query_map(rusqlite::params_from_iter(values), summary_from_row)?
+            .map(|summary| summary.map(|summary| (summary.id.clone(), summary)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ids.iter().filter_map(|id| summaries.remove(id)).collect())
+    }
+
+    /**
+     * (id, leading text) of the newest items matching the filter, pinned first
+     * The filter's search is ignored; callers rank the text themselves
+     */
+    pub fn get_match_candidates(
+        &self,
+        filter: &ClipboardQueryFilter,
+        text_chars: usize,
+        limit: u64,
+    ) -> SqliteResult<Vec<(String, String)>> {
+        let conn = self.conn.lock();
+        let filter = ClipboardQueryFilter {
+            search: None,
+            ..filter.clone()
+        };
+        let (query, values) = ItemQuery::select(&format!("id, substr(content, 1, {})", text_chars))
+            .filter(&filter)
+            .order_by("is_pinned DESC, timestamp DESC, id DESC")
+            .limit(limit)
+            .build();
+
+        let mut stmt = conn.prepare(&query)?;
+        let candidates = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(candidates)
+    }
+}
+
+/**
+ * Stored byte size of an item's text flavors and of its image data
+ * Externalized text counts at its full size (content_size), not the preview kept in the row
+ */
+/**
+ * How long a statement waits for another process's lock before SQLITE_BUSY
+ */
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+const TEXT_BYTES_SQL: &str = "COALESCE(content_size, length(CAST(content AS BLOB))) + COALESCE(length(CAST(html AS BLOB)), 0) + COALESCE(length(CAST(rtf AS BLOB)), 0)";
+const IMAGE_BYTES_SQL: &str = "COALESCE(length(image_base64), 0)";
+
+/**
+ * SHA-256 identifying an item's exact content, used for duplicate lookups
+ */
+fn content_hash(item_type: &str, content: &str, content_file: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    for part in [item_type, content, content_file.unwrap_or_default()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/**
+ * Items a new capture is compared against: same source, within the dedup window
+ */
+struct SourceScope<'a> {
+    source: &'a str,
+    window: &'a DedupWindow,
+}
+
+impl SourceScope<'_> {
+    fn apply(&self, query: ItemQuery) -> ItemQuery {
+        self.window
+            .apply(query.and_where("source = ?", [Value::Text(self.source.to_string())]))
+    }
+}
+
+fn find_duplicate_in(
+    conn: &Connection,
+    content_hash: &str,
+    scope: &SourceScope,
+) -> SqliteResult<Option<String>> {
+    let query = ItemQuery::select("id")
+        .and_where("content_hash = ?", [Value::Text(content_hash.to_string())]);
+    let (query, values) = scope
+        .apply(query)
+        .order_by("timestamp DESC")
+        .limit(1)
+        .build();
+
+    conn.query_row(&query, rusqlite::params_from_iter(values), |row| row.get(0))
+        .optional()
+}
+
+fn find_duplicate_image_in(
+    conn: &Connection,
+    image_hash: &str,
+    threshold: u32,
+    scope: &SourceScope,
+) -> SqliteResult<Option<String>> {
+    if threshold == 0 {
+        let query = ItemQuery::select("id")
+            .and_where("image_hash = ?", [Value::Text(image_hash.to_string())]);
+        let (query, values) = scope.apply(query).limit(1).build();
+        return conn
+            .query_row(&query, rusqlite::params_from_iter(values), |row| row.get(0))
+            .optional();
+    }
+
+    let query = ItemQuery::select("id, image_hash").and_where("image_hash IS NOT NULL", []);
+    let (query, values) = scope.apply(query).build();
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(values))?;
+    while let Some(row) = rows.next()? {
+        let existing: String = row.get(1)?;
+        if image_hash::distance(image_hash, &existing).is_some_and(|d| d <= threshold) {
+            return Ok(Some(row.get(0)?));
+        }
+    }
+
+    Ok(None)
+}
+
+fn find_duplicate_image_bytes_in(
+    conn: &Connection,
+    image_base64: &str,
+    scope: &SourceScope,
+) -> SqliteResult<Option<String>> {
+    let query = ItemQuery::select("id")
+        .and_where("image_base64 = ?", [Value::Text(image_base64.to_string())]);
+    let (query, values) = scope.apply(query).limit(1).build();
+    conn.query_row(&query, rusqlite::params_from_iter(values), |row| row.get(0))
+        .optional()
+}
+
+/**
+ * Move an item to the top of history and count the repeated copy as a use
+ */
+fn bump_item_in(conn: &Connection, id: &str) -> SqliteResult<usize> {
+    let now = Utc::now().timestamp_millis();
+    conn.execute(
+        "UPDATE clipboard_items SET timestamp = ?, updated_at = ?, use_count = use_count + 1 WHERE id = ?",
+        rusqlite::params![now, now, id],
+    )
+}
+
+/**
+ * Delete the oldest unpinned items beyond `max_items`
+ */
+fn enforce_max_items_in(conn: &Connection, max_items: i64) -> SqliteResult<usize> {
+    conn.execute(
+        r#"
+        DELETE FROM clipboard_items WHERE id IN (
+            SELECT id FROM clipboard_items 
+            WHERE is_pinned = 0
+            ORDER BY timestamp ASC 
+            LIMIT MAX(0, (SELECT COUNT(*) - ? FROM clipboard_items WHERE is_pinned = 0))
+        )
+        "#,
+        rusqlite::params![max_items],
+    )
+}
+
+/**
+ * Member of a near-duplicate group in consolidate_duplicates
+ */
+struct GroupedItem {
+    id: String,
+    use_count: i64,
+    is_pinned: bool,
+}
+
+/**
+ * SHA-256 of text with whitespace runs collapsed, for near-duplicate grouping
+ */
+fn normalized_hash(text: &str) -> String {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/**
+ * Month (YYYY-MM, UTC) of an item, and which items may be archived before ?1
+ */
+const MONTH_SQL: &str = "strftime('%Y-%m', timestamp / 1000, 'unixepoch')";
+const ARCHIVABLE_SQL: &str =
+    "is_pinned = 0 AND timestamp < ?1 AND id NOT IN (SELECT item_id FROM item_shortcuts)";
+
+/**
+ * Open an archive file, bringing its schema up to date
+ */
+fn open_archive(path: &Path) -> SqliteResult<Connection> {
+    let conn = Connection::open(path)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+/**
+ * SQLite URI opening `path` read-only, for ATTACH
+ */
+fn read_only_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    // Windows drive paths need an empty authority: file:///C:/...
+    let mut uri = match path.starts_with('/') {
+        true => "file:".to_string(),
+        false => "file:///".to_string(),
+    };
+    for c in path.chars() {
+        match c {
+            '%' => uri.push_str("%25"),
+            '?' => uri.push_str("%3f"),
+            '#' => uri.push_str("%23"),
+            c => uri.push(c),
+        }
+    }
+    uri.push_str("?mode=ro");
+    uri
+}
+
+/**
+ * YYYY-MM of an archive file name, None for other files
+ */
+fn archive_month(path: &Path) -> Option<&str> {
+    let month = path
+        .file_name()?
+        .to_str()?
+        .strip_prefix("copyclip-archive-")?
+        .strip_suffix(".db")?;
+    (month.len() == 7).then_some(month)
+}
+
+/**
+ * Copy one month of archivable items (and their files) into the attached archive, then delete them
+ */
+fn move_month_to_archive(conn: &mut Connection, month: &str, cutoff: i64) -> SqliteResult<usize> {
+    let tx = conn.transaction()?;
+    let selected = format!("{} AND {} = ?2", ARCHIVABLE_SQL, MONTH_SQL);
+
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO archive.clipboard_items ({cols}, content_hash) SELECT {cols}, content_hash FROM main.clipboard_items WHERE {selected}",
+            cols = ITEM_COLUMNS,
+            selected = selected
+        ),
+        rusqlite::params![cutoff, month],
+    )?;
+    // Items already in the archive (archived, restored, archived again) keep their file rows
+    tx.execute(
+        &format!(
+            "INSERT INTO archive.clipboard_files (item_id, path, size, mime, file_exists) SELECT item_id, path, size, mime, file_exists FROM main.clipboard_files AS f WHERE item_id IN (SELECT id FROM main.clipboard_items WHERE {}) AND NOT EXISTS (SELECT 1 FROM archive.clipboard_files AS a WHERE a.item_id = f.item_id AND a.path = f.path)",
+            selected
+        ),
+        rusqlite::params![cutoff, month],
+    )?;
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO archive.item_context_images SELECT * FROM main.item_context_images WHERE item_id IN (SELECT id FROM main.clipboard_items WHERE {})",
+            selected
+        ),
+        rusqlite::params![cutoff, month],
+    )?;
+    let moved = tx.execute(
+        &format!("DELETE FROM main.clipboard_items WHERE {}", selected),
+        rusqlite::params![cutoff, month],
+    )?;
+
+    tx.commit()?;
+    Ok(moved)
+}
+
+/**
+ * Characters of content included in list summaries
+ */
+const PREVIEW_CHARS: usize = 200;
+
+/**
+ * ORDER BY clause for a sort mode; ties fall back to newest first
+ * The expressions match the indexes created in init_schema
+ */
+fn sort_clause(sort_by: SortBy, direction: Option<SortDirection>) -> String {
+    let (key, natural) = match sort_by {
+        SortBy::Newest => ("timestamp", SortDirection::Desc),
+        SortBy::Oldest => ("timestamp", SortDirection::Asc),
+        SortBy::MostUsed => ("use_count", SortDirection::Desc),
+        SortBy::Largest => (
+            "length(content) + coalesce(length(image_base64), 0)",
+            SortDirection::Desc,
+        ),
+        SortBy::Alphabetical => ("content COLLATE NOCASE", SortDirection::Asc),
+        SortBy::Type => ("item_type", SortDirection::Asc),
+    };
+    let direction = match direction.unwrap_or(natural) {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    };
+    format!(
+        "is_pinned DESC, {} {}, timestamp DESC, id DESC",
+        key, direction
+    )
+}
+
+/**
+ * Columns selected for a full ClipboardItemModel, in item_from_row order
+ */
+const ITEM_COLUMNS: &str = "id, content, item_type, is_pinned, timestamp, image_base64, file_paths, created_at, updated_at, html, rtf, image_hash, use_count, source_url, source_title, color, language, content_file, content_size, original_content, source";
+
+/**
+ * Map a row selected with ITEM_COLUMNS to a model
+ */
+fn item_from_row(row: &rusqlite::Row) -> SqliteResult<ClipboardItemModel> {
+    Ok(ClipboardItemModel {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        item_type: row.get(2)?,
+        is_pinned: row.get(3)?,
+        timestamp: row.get(4)?,
+        image_base64: row.get(5)?,
+        file_paths: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        html: row.get(9)?,
+        rtf: row.get(10)?,
+        image_hash: row.get(11)?,
+        use_count: row.get(12)?,
+        source_url: row.get(13)?,
+        source_title: row.get(14)?,
+        color: row.get(15)?,
+        language: row.get(16)?,
+        content_file: row.get(17)?,
+        content_size: row.get(18)?,
+        original_content: row.get(19)?,
+        source: row.get(20)?,
+        library: None,
+        highlight: None,
+    })
+}
+
+/**
+ * Insert a full item row
+ */
+fn insert_item(conn: &Connection, item: &ClipboardItemModel) -> SqliteResult<usize> {
+    insert_item_into(conn, "clipboard_items", item)
+}
+
+fn insert_item_into(
+    conn: &Connection,
+    table: &str,
+    item: &ClipboardItemModel,
+) -> SqliteResult<usize> {
+    conn.execute(
+        &format!(
+            "INSERT INTO {} ({}, content_hash) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            table, ITEM_COLUMNS
+        ),
+        rusqlite::params![
+            &item.id,
+            &item.content,
+            &item.item_type,
+            item.is_pinned,
+            item.timestamp,
+            &item.image_base64,
+            &item.file_paths,
+            item.created_at,
+            item.updated_at,
+            &item.html,
+            &item.rtf,
+            &item.image_hash,
+            item.use_count,
+            &item.source_url,
+            &item.source_title,
+            &item.color,
+            &item.language,
+            &item.content_file,
+            item.content_size,
+            &item.original_content,
+            &item.source,
+            content_hash(&item.item_type, &item.content, item.content_file.as_deref()),
+        ],
+    )
+}
+
+/**
+ * Add a column to an existing table if it is missing (lightweight migration)
+ */
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> SqliteResult<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?",
+            table
+        ))?
+        .exists(rusqlite::params![column])?;
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Create tables and indexes, and migrate older schemas
+ */
+fn init_schema(conn: &Connection) -> SqliteResult<()> {
+    // Create table
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS clipboard_items (
+            id TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            item_type TEXT NOT NULL,
+            is_pinned BOOLEAN DEFAULT 0,
+            timestamp INTEGER NOT NULL,
+            image_base64 TEXT,
+            file_paths TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    // Columns added after the initial schema
+    ensure_column(conn, "clipboard_items", "html", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "rtf", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "image_hash", "TEXT")?;
+    ensure_column(
+        conn,
+        "clipboard_items",
+        "use_count",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    ensure_column(conn, "clipboard_items", "source_url", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "source_title", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "color", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "language", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "content_file", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "content_size", "INTEGER")?;
+    ensure_column(conn, "clipboard_items", "original_content", "TEXT")?;
+    ensure_column(conn, "clipboard_items", "content_hash", "TEXT")?;
+    ensure_column(
+        conn,
+        "clipboard_items",
+        "source",
+        "TEXT NOT NULL DEFAULT 'clipboard'",
+    )?;
+    backfill_content_hashes(conn)?;
+
+    // Create indexes
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_timestamp ON clipboard_items(timestamp DESC);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_is_pinned ON clipboard_items(is_pinned);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_item_type ON clipboard_items(item_type);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_language ON clipboard_items(language);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_content_hash ON clipboard_items(content_hash);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_image_hash ON clipboard_items(image_hash);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_use_count ON clipboard_items(use_count DESC);",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_item_size ON clipboard_items(length(content) + coalesce(length(image_base64), 0));",
+        [],
+    )?;
+
+    // Files referenced by 'file' items, one row per path
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS clipboard_files (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id TEXT NOT NULL REFERENCES clipboard_items(id) ON DELETE CASCADE,
+            path TEXT NOT NULL,
+            size INTEGER,
+            mime TEXT,
+            file_exists BOOLEAN NOT NULL DEFAULT 1
+        )
+        "#,
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_files_item_id ON clipboard_files(item_id);",
+        [],
+    )?;
+
+    // Screenshot of the window an item was copied from (opt-in)
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS item_context_images (
+            item_id TEXT PRIMARY KEY REFERENCES clipboard_items(id) ON DELETE CASCADE,
+            image_base64 TEXT NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            captured_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    // Shortcut -> pinned item bindings
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS item_shortcuts (
+            shortcut TEXT PRIMARY KEY,
+            item_id TEXT NOT NULL REFERENCES clipboard_items(id) ON DELETE CASCADE,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    // App launcher aliases
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS app_aliases (
+            alias TEXT PRIMARY KEY COLLATE NOCASE,
+            target TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    // Audit trail of destructive operations
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            operation TEXT NOT NULL,
+            detail TEXT,
+            affected INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_audit_created_at ON audit_log(created_at DESC);",
+        [],
+    )?;
+
+    // Outbound webhook delivery log
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            delivered INTEGER NOT NULL,
+            attempts INTEGER NOT NULL,
+            http_status INTEGER,
+            error TEXT,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_created_at ON webhook_deliveries(created_at DESC);",
+        [],
+    )?;
+
+    // Opt-in local usage counters, one row per day and metric
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS usage_metrics (
+            day TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            total_ms INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, metric)
+        )
+        "#,
+        [],
+    )?;
+
+    // Key/value app settings
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+        [],
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Hash rows saved before content_hash existed (or copied into archives without it)
+ */
+fn backfill_content_hashes(conn: &Connection) -> SqliteResult<()> {
+    let rows = conn
+        .prepare("SELECT id, item_type, content, content_file FROM clipboard_items WHERE content_hash IS NULL")?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                content_hash(
+                    &row.get::<_, String>(1)?,
+                    &row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?.as_deref(),
+                ),
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (id, hash) in rows {
+        conn.execute(
+            "UPDATE clipboard_items SET content_hash = ? WHERE id = ?",
+            rusqlite::params![hash, id],
+        )?;
+    }
+    Ok(())
+}
+
+/**
+ * Rewrite stored shortcuts bound before key names were canonicalized
+ * Unparsable shortcuts are left alone so they stay visible and can be unbound
+ */
+fn canonicalize_shortcuts(conn: &Connection) -> SqliteResult<()> {
+    let shortcuts = conn
+        .prepare("SELECT shortcut FROM item_shortcuts")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for shortcut in shortcuts {
+        if let Ok(canonical) = keys::normalize_shortcut(&shortcut) {
+            if canonical != shortcut {
+                conn.execute(
+                    "UPDATE OR IGNORE item_shortcuts SET shortcut = ? WHERE shortcut = ?",
+                    rusqlite::params![canonical, shortcut],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Check that a file is a readable, intact copyclip database
+ */
+fn validate_backup(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let check: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+    if check != "ok" {
+        return Err(format!("Backup failed integrity check: {}", check).into());
+    }
+
+    let has_items = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'clipboard_items'")?
+        .exists([])?;
+    if !has_items {
+        return Err("File is not a copyclip database".into());
+    }
+
+    Ok(())
+}
+
+/**
+ * Columns read by summary_from_row, in order
+ */
+fn summary_columns() -> String {
+    format!(
+        "id, substr(content, 1, {}), length(content), item_type, is_pinned, image_base64 IS NOT NULL, timestamp, created_at, updated_at, language, COALESCE(content_size, length(CAST(content AS BLOB))), content_file IS NOT NULL",
+        PREVIEW_CHARS
+    )
+}
+
+/**
+ * Map a row selected with summary_columns to a summary without highlight
+ */
+fn summary_from_row(row: &rusqlite::Row) -> SqliteResult<ClipboardItemSummaryModel> {
+    Ok(ClipboardItemSummaryModel {
+        id: row.get(0)?,
+        preview: row.get(1)?,
+        content_length: row.get(2)?,
+        content_size: row.get(10)?,
+        is_truncated: row.get(11)?,
+        item_type: row.get(3)?,
+        is_pinned: row.get(4)?,
+        has_image: row.get(5)?,
+        timestamp: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        language: row.get(9)?,
+        highlight: None,
+    })
+}
+
+/**
+ * Encode the sort key of the last row of a page as an opaque cursor
+ */
+fn encode_cursor(item: &ClipboardItemSummaryModel) -> String {
+    let key = format!("{}:{}:{}", item.is_pinned as i64, item.timestamp, item.id);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(key)
+}
+
+/**
+ * Decode a cursor into (is_pinned, timestamp, id), None if malformed
+ */
+fn decode_cursor(cursor: &str) -> Option<(i64, i64, String)> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()?;
+    let key = String::from_utf8(bytes).ok()?;
+
+    let mut parts = key.splitn(3, ':');
+    let pinned = parts.next()?.parse().ok().filter(|p| *p == 0 || *p == 1)?;
+    let timestamp = parts.next()?.parse().ok()?;
+    let id = parts.next()?.to_string();
+    Some((pinned, timestamp, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> (DatabaseService, PathBuf) {
+        let path = std::env::temp_dir().join(format!("copyclip-test-{}.db", uuid::Uuid::new_v4()));
+        (DatabaseService::new(path.clone()).unwrap(), path)
+    }
+
+    fn add(db: &DatabaseService, content: &str, timestamp: i64) -> String {
+        let mut item = ClipboardItemModel::new(
+            uuid::Uuid::new_v4().to_string(),
+            content.to_string(),
+            "text".to_string(),
+            None,
+            None,
+        );
+        item.timestamp = timestamp;
+        insert_item(&db.conn.lock(), &item).unwrap();
+        item.id
+    }
+
+    #[test]
+    fn items_page_rejects_bad_cursors() {
+        let (db, path) = temp_db();
+        for (i, content) in ["a", "b", "c"].iter().enumerate() {
+            add(&db, content, 1_000 + i as i64);
+        }
+        let filter = ClipboardQueryFilter {
+            limit: 2,
+            ..Default::default()
+        };
+
+        let first = db.get_items_page(&filter, None).unwrap();
+        let cursor = first.next_cursor.unwrap();
+        let second = db.get_items_page(&filter, Some(&cursor)).unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].preview, "a");
+
+        assert!(db.get_items_page(&filter, Some("not a cursor")).is_err());
+        let forged = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode("7:1000:x");
+        assert!(db.get_items_page(&filter, Some(&forged)).is_err());
+
+        // The last item of the first page is copied again and moves to the top
+        let last = first.items.last().unwrap();
+        db.conn
+            .lock()
+            .execute(
+                "UPDATE clipboard_items SET timestamp = 2000 WHERE id = ?",
+                [&last.id],
+            )
+            .unwrap();
+        assert!(db.get_items_page(&filter, Some(&cursor)).is_err());
 
-        eprintln!("[DB::CHECK_DUP] Query result: count={}", count);
-        Ok(count > 0)
+        drop(db);
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -0,0 +1,45 @@
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::DatabaseService;
+use crate::shutdown::Shutdown;
+
+/**
+ * Emitted when another process (second instance, sync tool) commits to the database
+ * The frontend reloads settings and history on it
+ */
+pub const CHANGED_EVENT: &str = "database://changed";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/**
+ * Poll SQLite's data_version to notice external writes without restarting
+ */
+pub fn start(app: AppHandle) {
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("db-watcher", move |token| {
+        let mut last: Option<i64> = None;
+
+        while token.sleep(POLL_INTERVAL) {
+            let Some(db) = app.try_state::<DatabaseService>() else {
+                continue;
+            };
+
+            let version = match db.data_version() {
+                Ok(version) => version,
+                Err(e) => {
+                    log::error!("Failed to read database data_version: {}", e);
+                    continue;
+                }
+            };
+
+            if last.is_some_and(|last| last != version) {
+                log::info!("Database changed externally, notifying frontend");
+                if let Err(e) = app.emit(CHANGED_EVENT, ()) {
+                    log::error!("Failed to emit {}: {}", CHANGED_EVENT, e);
+                }
+            }
+            last = Some(version);
+        }
+    });
+}
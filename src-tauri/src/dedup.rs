@@ -0,0 +1,95 @@
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::db::DatabaseService;
+use crate::query::ItemQuery;
+
+pub const POLICY_SETTING: &str = "dedup_policy";
+pub const WINDOW_MINUTES_SETTING: &str = "dedup_window_minutes";
+pub const WINDOW_ITEMS_SETTING: &str = "dedup_window_items";
+
+/**
+ * Window used by the "window" policy when neither limit is configured
+ */
+const DEFAULT_WINDOW_MINUTES: u64 = 10;
+
+/**
+ * What to do when a copy matches an item already in history
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupPolicy {
+    Skip,   // never save duplicates
+    Bump,   // move the existing item to the top
+    Window, // only skip duplicates of recent items
+}
+
+/**
+ * Which items count as duplicates; all of history when both are None
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DedupWindow {
+    pub since: Option<i64>,      // ms timestamp
+    pub last_items: Option<u64>, // N most recent items
+}
+
+impl DedupWindow {
+    /**
+     * Restrict a duplicate lookup to the window
+     */
+    pub fn apply(&self, mut query: ItemQuery) -> ItemQuery {
+        if let Some(since) = self.since {
+            query = query.and_where("timestamp >= ?", [Value::Integer(since)]);
+        }
+        if let Some(last_items) = self.last_items {
+            query = query.and_where(
+                "id IN (SELECT id FROM clipboard_items ORDER BY timestamp DESC LIMIT ?)",
+                [Value::Integer(
+                    i64::try_from(last_items).unwrap_or(i64::MAX),
+                )],
+            );
+        }
+        query
+    }
+}
+
+/**
+ * Dedup policy loaded from settings
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Dedup {
+    pub policy: DedupPolicy,
+    pub window: DedupWindow,
+}
+
+impl Dedup {
+    pub fn from_settings(db: &DatabaseService) -> Self {
+        let setting = |key: &str| db.get_setting(key).ok().flatten();
+        let number = |key: &str| setting(key).and_then(|value| value.parse::<u64>().ok());
+
+        let policy = match setting(POLICY_SETTING).as_deref() {
+            Some("bump") => DedupPolicy::Bump,
+            Some("window") => DedupPolicy::Window,
+            _ => DedupPolicy::Skip,
+        };
+
+        let window = match policy {
+            DedupPolicy::Window => {
+                let last_items = number(WINDOW_ITEMS_SETTING).filter(|n| *n > 0);
+                let minutes = number(WINDOW_MINUTES_SETTING)
+                    .filter(|n| *n > 0)
+                    .or(last_items.is_none().then_some(DEFAULT_WINDOW_MINUTES));
+                DedupWindow {
+                    since: minutes.map(|minutes| {
+                        chrono::Utc::now().timestamp_millis()
+                            - i64::try_from(minutes.saturating_mul(60_000)).unwrap_or(i64::MAX)
+                    }),
+                    last_items,
+                }
+            }
+            _ => DedupWindow::default(),
+        };
+
+        Self { policy, window }
+    }
+}
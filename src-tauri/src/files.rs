@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use crate::models::ClipboardFileModel;
+
+/**
+ * Read file metadata for a copied path
+ * Missing files are kept with exists = false so history still shows them
+ */
+pub fn inspect(item_id: &str, path: &str) -> ClipboardFileModel {
+    let file_path = Path::new(path);
+    let metadata = std::fs::metadata(file_path).ok();
+
+    ClipboardFileModel {
+        item_id: item_id.to_string(),
+        path: path.to_string(),
+        size: metadata
+            .as_ref()
+            .filter(|m| m.is_file())
+            .map(|m| m.len() as i64),
+        mime: guess_mime(file_path, metadata.as_ref().is_some_and(|m| m.is_dir())),
+        exists: metadata.is_some(),
+    }
+}
+
+/**
+ * Parse the file_paths JSON array stored on an item
+ * Accepts plain paths and file:// URIs
+ */
+pub fn parse_paths(file_paths: &str) -> Result<Vec<String>, serde_json::Error> {
+    let paths: Vec<String> = serde_json::from_str(file_paths)?;
+    Ok(paths
+        .into_iter()
+        .map(|path| match path.strip_prefix("file://") {
+            Some(uri) => uri_path(uri),
+            None => path,
+        })
+        .collect())
+}
+
+/**
+ * Local path of a file URI without its scheme, e.g. "localhost/tmp/a%20b.txt" -> "/tmp/a b.txt"
+ */
+fn uri_path(uri: &str) -> String {
+    let uri = uri.strip_prefix("localhost").unwrap_or(uri);
+    let path = percent_decode(uri);
+
+    // file:///C:/Users -> C:/Users
+    #[cfg(windows)]
+    if let Some(rest) = path.strip_prefix('/') {
+        if rest.as_bytes().get(1) == Some(&b':') {
+            return rest.to_string();
+        }
+    }
+    path
+}
+
+/**
+ * Decode %XX escapes; malformed escapes are kept as they are
+ */
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match (bytes[i], bytes.get(i + 1), bytes.get(i + 2)) {
+            (b'%', Some(high), Some(low)) => {
+                let hex = |b: u8| (b as char).to_digit(16);
+                hex(*high).zip(hex(*low)).map(|(h, l)| (h * 16 + l) as u8)
+            }
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn guess_mime(path: &Path, is_dir: bool) -> Option<String> {
+    if is_dir {
+        return Some("inode/directory".to_string());
+    }
+
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    let mime = match ext.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    };
+    Some(mime.to_string())
+}
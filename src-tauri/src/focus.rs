@@ -0,0 +1,186 @@
+use parking_lot::Mutex;
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+use std::process::Command;
+
+use crate::capabilities::{self, Feature};
+
+/**
+ * How long the target app gets to take focus before the paste keystroke
+ */
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+const FOCUS_SETTLE: std::time::Duration = std::time::Duration::from_millis(150);
+
+/**
+ * The app that had focus before copyclip's window was shown
+ * Holds a platform id: process id (macOS), X11 window id (Linux), HWND (Windows)
+ */
+pub struct FocusMemory {
+    previous: Mutex<Option<String>>,
+}
+
+impl FocusMemory {
+    pub fn new() -> Self {
+        Self {
+            previous: Mutex::new(None),
+        }
+    }
+
+    /**
+     * Record the focused app; call before the main window takes focus
+     */
+    pub fn remember(&self) {
+        let previous = match active_window() {
+            Ok(previous) => previous,
+            Err(e) => {
+                log::debug!("Could not read the focused window: {}", e);
+                None
+            }
+        };
+        *self.previous.lock() = previous;
+    }
+
+    pub fn previous(&self) -> Option<String> {
+        self.previous.lock().clone()
+    }
+}
+
+impl Default for FocusMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Give focus back to `previous` and send the paste keystroke
+ * Without a remembered app the keystroke goes to whatever has focus once copyclip is hidden
+ */
+pub fn return_and_paste(previous: Option<&str>) -> Result<(), String> {
+    capabilities::require(Feature::PasteAndReturn)?;
+    paste_into(previous)
+}
+
+#[cfg(target_os = "macos")]
+fn active_window() -> Result<Option<String>, String> {
+    let pid = output(Command::new("osascript").args([
+        "-e",
+        "tell application \"System Events\" to get unix id of first process whose frontmost is true",
+    ]))?;
+    // Our own window being frontmost means there is nothing to return to
+    Ok(Some(pid).filter(|pid| *pid != std::process::id().to_string()))
+}
+
+#[cfg(target_os = "macos")]
+fn paste_into(previous: Option<&str>) -> Result<(), String> {
+    if let Some(pid) = previous.filter(|pid| pid.chars().all(|c| c.is_ascii_digit())) {
+        output(Command::new("osascript").args([
+            "-e",
+            &format!(
+                "tell application \"System Events\" to set frontmost of first process whose unix id is {} to true",
+                pid
+            ),
+        ]))?;
+    }
+    std::thread::sleep(FOCUS_SETTLE);
+    output(Command::new("osascript").args([
+        "-e",
+        "tell application \"System Events\" to keystroke \"v\" using command down",
+    ]))?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn active_window() -> Result<Option<String>, String> {
+    // Wayland doesn't expose the focused window, hiding copyclip hands focus back instead
+    if capabilities::display_server() == Some(capabilities::DisplayServer::Wayland) {
+        return Ok(None);
+    }
+    let id = output(Command::new("xdotool").arg("getactivewindow"))?;
+    Ok(Some(id).filter(|id| !id.is_empty()))
+}
+
+#[cfg(target_os = "linux")]
+fn paste_into(previous: Option<&str>) -> Result<(), String> {
+    if capabilities::display_server() == Some(capabilities::DisplayServer::Wayland) {
+        std::thread::sleep(FOCUS_SETTLE);
+        if capabilities::which("wtype") {
+            output(Command::new("wtype").args(["-M", "ctrl", "v", "-m", "ctrl"]))?;
+        } else {
+            // Linux input event codes: 29 is left ctrl, 47 is v
+            output(Command::new("ydotool").args(["key", "29:1", "47:1", "47:0", "29:0"]))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(id) = previous {
+        output(Command::new("xdotool").args(["windowactivate", "--sync", id]))?;
+    }
+    std::thread::sleep(FOCUS_SETTLE);
+    output(Command::new("xdotool").args(["key", "--clearmodifiers", "ctrl+v"]))?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+const USER32: &str = r#"Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public static class Focus {
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll")] public static extern bool SetForegroundWindow(IntPtr hWnd);
+}
+"@;"#;
+
+#[cfg(target_os = "windows")]
+fn active_window() -> Result<Option<String>, String> {
+    let script = format!("{}\n[Focus]::GetForegroundWindow().ToInt64()", USER32);
+    let hwnd = output(Command::new("powershell").args(["-NoProfile", "-Command", &script]))?;
+    Ok(Some(hwnd).filter(|hwnd| !hwnd.is_empty() && hwnd != "0"))
+}
+
+#[cfg(target_os = "windows")]
+fn paste_into(previous: Option<&str>) -> Result<(), String> {
+    let activate = match previous.filter(|hwnd| hwnd.chars().all(|c| c.is_ascii_digit())) {
+        Some(hwnd) => format!(
+            "[Focus]::SetForegroundWindow([IntPtr]{}) | Out-Null;\nStart-Sleep -Milliseconds {};",
+            hwnd,
+            FOCUS_SETTLE.as_millis()
+        ),
+        None => String::new(),
+    };
+    let script = format!(
+        "{}\n{}\nAdd-Type -AssemblyName System.Windows.Forms;\n[System.Windows.Forms.SendKeys]::SendWait('^v');",
+        USER32, activate
+    );
+    output(Command::new("powershell").args(["-NoProfile", "-Command", &script]))?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn active_window() -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn paste_into(_previous: Option<&str>) -> Result<(), String> {
+    Err("Pasting into other applications is not supported on this platform".to_string())
+}
+
+/**
+ * Run a helper and return its trimmed stdout
+ */
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+fn output(cmd: &mut Command) -> Result<String, String> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,93 @@
+use crate::models::{HighlightSegmentModel, SearchHighlightModel};
+
+/**
+ * Characters of context kept before the first match, and the snippet length
+ * get_items_page cuts the same window in SQL
+ */
+pub const CONTEXT_BEFORE: usize = 60;
+pub const SNIPPET_CHARS: usize = 240;
+
+/**
+ * Snippet around the first match of `search` in `content`, None without a match
+ * Matching is ASCII case-insensitive like the LIKE search itself
+ */
+pub fn highlight(content: &str, search: &str) -> Option<SearchHighlightModel> {
+    let first = find_matches(content, search).next()?.0;
+    let match_char = content[..first].chars().count();
+    let start_char = match_char.saturating_sub(CONTEXT_BEFORE);
+
+    let start = byte_offset(content, start_char);
+    let end = start + byte_offset(&content[start..], SNIPPET_CHARS);
+    Some(build(
+        &content[start..end],
+        search,
+        start > 0,
+        end < content.len(),
+    ))
+}
+
+/**
+ * Highlight an already cut snippet (see get_items_page)
+ */
+pub fn build(
+    snippet: &str,
+    search: &str,
+    truncated_start: bool,
+    truncated_end: bool,
+) -> SearchHighlightModel {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for (start, end) in find_matches(snippet, search) {
+        if start > last {
+            segments.push(segment(&snippet[last..start], false));
+        }
+        segments.push(segment(&snippet[start..end], true));
+        last = end;
+    }
+    if last < snippet.len() {
+        segments.push(segment(&snippet[last..], false));
+    }
+
+    SearchHighlightModel {
+        segments,
+        truncated_start,
+        truncated_end,
+    }
+}
+
+/**
+ * Non-overlapping byte ranges of `search` in `text`
+ * Ranges fall on char boundaries: a UTF-8 lead byte never equals a continuation byte
+ */
+fn find_matches<'a>(text: &'a str, search: &'a str) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let (haystack, needle) = (text.as_bytes(), search.as_bytes());
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        if needle.is_empty() {
+            return None;
+        }
+        while pos + needle.len() <= haystack.len() {
+            let start = pos;
+            if haystack[start..start + needle.len()].eq_ignore_ascii_case(needle) {
+                pos = start + needle.len();
+                return Some((start, pos));
+            }
+            pos += 1;
+        }
+        None
+    })
+}
+
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map(|(offset, _)| offset)
+        .unwrap_or(text.len())
+}
+
+fn segment(text: &str, matched: bool) -> HighlightSegmentModel {
+    HighlightSegmentModel {
+        text: text.to_string(),
+        matched,
+    }
+}
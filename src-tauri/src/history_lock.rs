@@ -0,0 +1,371 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::DatabaseService;
+use crate::shutdown::Shutdown;
+
+/**
+ * Argon2 PHC string of the master password; history can only be locked while set
+ */
+pub const PASSWORD_SETTING: &str = "history_lock_password";
+/**
+ * Minutes without history access before auto-locking, 0 disables auto-lock
+ */
+pub const IDLE_MINUTES_SETTING: &str = "history_lock_idle_minutes";
+/**
+ * Mirror of the lock state for other processes (native messaging host)
+ */
+pub const LOCKED_SETTING: &str = "history_locked";
+
+/**
+ * Emitted on lock/unlock; the picker shows the unlock prompt while locked
+ */
+pub const LOCKED_EVENT: &str = "history://locked";
+pub const UNLOCKED_EVENT: &str = "history://unlocked";
+
+pub const LOCKED_ERROR: &str = "History is locked";
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/**
+ * Lock state returned to the frontend
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryLockStatus {
+    pub locked: bool,
+    pub has_password: bool,
+    pub idle_minutes: u64,
+    pub biometric_available: bool,
+}
+
+/**
+ * History lock gate
+ * While locked every command reading history content fails with LOCKED_ERROR
+ */
+pub struct HistoryLock {
+    locked: AtomicBool,
+    last_activity: Mutex<Instant>,
+}
+
+impl HistoryLock {
+    pub fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            last_activity: Mutex::new(Instant::now()),
+        }
+    }
+
+    /**
+     * Fail while locked, otherwise count as activity for the idle timer
+     */
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_locked() {
+            return Err(LOCKED_ERROR.to_string());
+        }
+        *self.last_activity.lock() = Instant::now();
+        Ok(())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self, db: &DatabaseService) -> HistoryLockStatus {
+        HistoryLockStatus {
+            locked: self.is_locked(),
+            has_password: has_password(db),
+            idle_minutes: idle_minutes(db),
+            biometric_available: biometric_available(),
+        }
+    }
+
+    /**
+     * Lock history; requires a master password so it can be unlocked again
+     */
+    pub fn lock(&self, app: &AppHandle, db: &DatabaseService) -> Result<(), String> {
+        if !has_password(db) {
+            return Err("Set a master password before locking history".to_string());
+        }
+        if !self.locked.swap(true, Ordering::SeqCst) {
+            self.changed(app, db, true);
+        }
+        Ok(())
+    }
+
+    /**
+     * Unlock with the master password, or with the OS biometric prompt when None
+     * Returns false when the password or biometric check fails
+     */
+    pub fn unlock(
+        &self,
+        app: &AppHandle,
+        db: &DatabaseService,
+        password: Option<&str>,
+    ) -> Result<bool, String> {
+        let verified = match password {
+            Some(password) => verify_password(db, password)?,
+            None if biometric_available() => verify_biometric()?,
+            None => return Err("Biometric unlock is not available".to_string()),
+        };
+
+        if verified && self.locked.swap(false, Ordering::SeqCst) {
+            *self.last_activity.lock() = Instant::now();
+            self.changed(app, db, false);
+        }
+        Ok(verified)
+    }
+
+    /**
+     * Drop the lock without verification (after the password was removed)
+     */
+    pub fn release(&self, app: &AppHandle, db: &DatabaseService) {
+        if self.locked.swap(false, Ordering::SeqCst) {
+            self.changed(app, db, false);
+        }
+    }
+
+    fn changed(&self, app: &AppHandle, db: &DatabaseService, locked: bool) {
+        if let Err(e) = db.set_setting(LOCKED_SETTING, if locked { "true" } else { "false" }) {
+            log::error!("Failed to store history lock state: {}", e);
+        }
+
+        let event = if locked { LOCKED_EVENT } else { UNLOCKED_EVENT };
+        if let Err(e) = app.emit(event, ()) {
+            log::error!("Failed to emit {}: {}", event, e);
+        }
+        log::info!("History {}", if locked { "locked" } else { "unlocked" });
+    }
+}
+
+impl Default for HistoryLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Start locked when a master password is set, then auto-lock after the idle timeout
+ */
+pub fn start(app: AppHandle) {
+    if let (Some(lock), Some(db)) = (
+        app.try_state::<HistoryLock>(),
+        app.try_state::<DatabaseService>(),
+    ) {
+        if has_password(&db) {
+            let _ = lock.lock(&app, &db);
+        } else {
+            lock.changed(&app, &db, false);
+        }
+    }
+
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("history-lock-idle", move |token| {
+        while token.sleep(IDLE_CHECK_INTERVAL) {
+            let (Some(lock), Some(db)) = (
+                app.try_state::<HistoryLock>(),
+                app.try_state::<DatabaseService>(),
+            ) else {
+                continue;
+            };
+
+            let minutes = idle_minutes(&db);
+            if minutes == 0 || lock.is_locked() {
+                continue;
+            }
+
+            let idle = lock.last_activity.lock().elapsed();
+            if idle >= Duration::from_secs(minutes * 60) && lock.lock(&app, &db).is_ok() {
+                log::info!("History auto-locked after {} idle minutes", minutes);
+            }
+        }
+    });
+}
+
+/**
+ * Set, change or remove (None) the master password
+ * Changing or removing an existing password requires the current one
+ */
+pub fn set_password(
+    db: &DatabaseService,
+    current: Option<&str>,
+    new: Option<&str>,
+) -> Result<(), String> {
+    if has_password(db) && !verify_password(db, current.unwrap_or_default())? {
+        return Err("Current password is incorrect".to_string());
+    }
+
+    let stored = match new {
+        Some("") => return Err("Password must not be empty".to_string()),
+        Some(password) => {
+            let salt = SaltString::generate(&mut OsRng);
+            let hash = Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|e| format!("Failed to hash password: {}", e))?;
+            db.set_setting(PASSWORD_SETTING, &hash.to_string())
+        }
+        None => db.delete_setting(PASSWORD_SETTING),
+    };
+    stored.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/**
+ * Whether the history lock owns a setting (not writable through set_setting)
+ */
+pub fn is_protected_setting(key: &str) -> bool {
+    key == PASSWORD_SETTING || key == LOCKED_SETTING
+}
+
+/**
+ * Whether a master password is set
+ * Other processes sharing the database can't prove the user unlocked, so they treat this as locked
+ */
+pub fn has_password(db: &DatabaseService) -> bool {
+    db.get_setting(PASSWORD_SETTING).ok().flatten().is_some()
+}
+
+fn idle_minutes(db: &DatabaseService) -> u64 {
+    db.get_setting(IDLE_MINUTES_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+fn verify_password(db: &DatabaseService, password: &str) -> Result<bool, String> {
+    let Some(stored) = db
+        .get_setting(PASSWORD_SETTING)
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(false);
+    };
+    let hash = PasswordHash::new(&stored).map_err(|e| format!("Invalid password hash: {}", e))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok())
+}
+
+#[cfg(target_os = "linux")]
+fn biometric_available() -> bool {
+    std::process::Command::new("fprintd-list")
+        .arg(std::env::var("USER").unwrap_or_default())
+        .output()
+        .map(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout).contains("Fingerprints for user")
+        })
+        .unwrap_or(false)
+}
+
+/**
+ * Ask fprintd for a fingerprint match
+ */
+#[cfg(target_os = "linux")]
+fn verify_biometric() -> Result<bool, String> {
+    let status = std::process::Command::new("fprintd-verify")
+        .status()
+        .map_err(|e| format!("Failed to run fprintd-verify: {}", e))?;
+    Ok(status.success())
+}
+
+/**
+ * Loads the WinRT UserConsentVerifier and an awaiter for its async operations
+ */
+#[cfg(target_os = "windows")]
+const WINDOWS_HELLO_PRELUDE: &str = r#"Add-Type -AssemblyName System.Runtime.WindowsRuntime;
+$asTask = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object { $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' })[0];
+[Windows.Security.Credentials.UI.UserConsentVerifier,Windows.Security.Credentials.UI,ContentType=WindowsRuntime] | Out-Null;
+function Await($op, $type) { $task = $asTask.MakeGenericMethod($type).Invoke($null, @($op)); $task.Wait(-1) | Out-Null; $task.Result }
+"#;
+
+/**
+ * Whether Windows Hello has an authenticator set up
+ */
+#[cfg(target_os = "windows")]
+fn biometric_available() -> bool {
+    let script = format!(
+        "{}if ((Await ([Windows.Security.Credentials.UI.UserConsentVerifier]::CheckAvailabilityAsync()) ([Windows.Security.Credentials.UI.UserConsentVerifierAvailability])) -eq 'Available') {{ exit 0 }} else {{ exit 1 }}",
+        WINDOWS_HELLO_PRELUDE
+    );
+    std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/**
+ * Windows Hello consent prompt through the WinRT UserConsentVerifier
+ */
+#[cfg(target_os = "windows")]
+fn verify_biometric() -> Result<bool, String> {
+    let script = format!(
+        "{}if ((Await ([Windows.Security.Credentials.UI.UserConsentVerifier]::RequestVerificationAsync('Unlock CopyClip history')) ([Windows.Security.Credentials.UI.UserConsentVerificationResult])) -eq 'Verified') {{ exit 0 }} else {{ exit 1 }}",
+        WINDOWS_HELLO_PRELUDE
+    );
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .status()
+        .map_err(|e| format!("Failed to run Windows Hello: {}", e))?;
+    Ok(status.success())
+}
+
+/**
+ * LocalAuthentication policy 1: deviceOwnerAuthenticationWithBiometrics (Touch ID)
+ */
+#[cfg(target_os = "macos")]
+const TOUCH_ID_PRELUDE: &str =
+    "ObjC.import('LocalAuthentication'); const context = $.LAContext.alloc.init;";
+
+/**
+ * Whether Touch ID is present and enrolled
+ */
+#[cfg(target_os = "macos")]
+fn biometric_available() -> bool {
+    let script = format!(
+        "{} context.canEvaluatePolicyError(1, null)",
+        TOUCH_ID_PRELUDE
+    );
+    std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", &script])
+        .output()
+        .map(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true"
+        })
+        .unwrap_or(false)
+}
+
+/**
+ * Touch ID prompt through LocalAuthentication, the reply is awaited on the run loop
+ */
+#[cfg(target_os = "macos")]
+fn verify_biometric() -> Result<bool, String> {
+    let script = format!(
+        "{} let done = false, verified = false;
+context.evaluatePolicyLocalizedReasonReply(1, 'unlock CopyClip history', (ok, error) => {{ verified = ok; done = true; }});
+while (!done) {{ $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1)); }}
+verified",
+        TOUCH_ID_PRELUDE
+    );
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", &script])
+        .output()
+        .map_err(|e| format!("Failed to run Touch ID: {}", e))?;
+    Ok(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn biometric_available() -> bool {
+    false
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn verify_biometric() -> Result<bool, String> {
+    Err("Biometric unlock is not supported on this platform".to_string())
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::db::DatabaseService;
+use crate::url_tracking::{self, TrackingParams};
+
+/**
+ * JSON list of CaptureHook run on every newly saved text item
+ */
+pub const HOOKS_SETTING: &str = "capture_hooks";
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+
+/**
+ * What a capture hook does
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookKind {
+    TrimWhitespace,
+    StripUrlTracking,
+    Script, // runs `command` with the text on stdin, stdout replaces it
+}
+
+/**
+ * User-configured on_item_saved hook
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureHook {
+    pub name: String,
+    pub kind: HookKind,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/**
+ * Load configured hooks; a malformed setting disables all hooks
+ */
+pub fn load(db: &DatabaseService) -> Vec<CaptureHook> {
+    let Some(value) = db.get_setting(HOOKS_SETTING).ok().flatten() else {
+        return Vec::new();
+    };
+
+    serde_json::from_str(&value).unwrap_or_else(|e| {
+        log::error!("Ignoring invalid {} setting: {}", HOOKS_SETTING, e);
+        Vec::new()
+    })
+}
+
+/**
+ * Check a hook list before it is stored
+ */
+pub fn validate(hooks: &[CaptureHook]) -> Result<(), String> {
+    for hook in hooks {
+        if hook.kind == HookKind::Script
+            && hook.command.as_deref().is_none_or(|c| c.trim().is_empty())
+        {
+            return Err(format!("Hook '{}' needs a command", hook.name));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Run enabled hooks in order over the text
+ * A failing hook is logged and skipped, leaving the text as it was
+ */
+pub fn run(hooks: &[CaptureHook], text: String) -> String {
+    hooks
+        .iter()
+        .filter(|hook| hook.enabled)
+        .fold(text, |text, hook| match apply(hook, &text) {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("Capture hook '{}' failed: {}", hook.name, e);
+                text
+            }
+        })
+}
+
+fn apply(hook: &CaptureHook, text: &str) -> Result<String, String> {
+    match hook.kind {
+        HookKind::TrimWhitespace => Ok(text.trim().to_string()),
+        HookKind::StripUrlTracking => Ok(url_tracking::strip(text, &TrackingParams::default())),
+        HookKind::Script => {
+            let command = hook.command.as_deref().unwrap_or_default();
+            let timeout = Duration::from_millis(hook.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+            run_script(command, text, timeout)
+        }
+    }
+}
+
+/**
+ * Pipe text through a shell command, killing it after `timeout`
+ */
+fn run_script(command: &str, text: &str, timeout: Duration) -> Result<String, String> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("failed to start: {}", e))?;
+
+    // Feed stdin and drain stdout on threads so a chatty script cannot block on a full pipe
+    let mut stdin = child.stdin.take().ok_or("no stdin")?;
+    let input = text.to_string();
+    std::thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+
+    let mut stdout = child.stdout.take().ok_or("no stdout")?;
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("timed out after {:?}", timeout));
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    if !status.success() {
+        return Err(format!("exited with {}", status));
+    }
+
+    let output = reader
+        .join()
+        .map_err(|_| "output reader panicked".to_string())?
+        .map_err(|e| format!("invalid output: {}", e))?;
+
+    // Empty output means "leave the text alone"
+    if output.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+    Ok(output.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
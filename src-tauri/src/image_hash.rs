@@ -0,0 +1,42 @@
+use base64::Engine;
+
+/**
+ * Perceptual difference hash (dHash) of a base64-encoded image
+ * Visually identical images (re-encoded, slightly resized) hash within a few bits
+ */
+pub fn dhash(image_base64: &str) -> Option<String> {
+    let data = image_base64
+        .split_once("base64,")
+        .map(|(_, data)| data)
+        .unwrap_or(image_base64);
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .ok()?;
+    let image = image::load_from_memory(&bytes).ok()?;
+
+    // 9x8 grayscale: compare each pixel with its right neighbour
+    let small = image
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+
+    Some(format!("{:016x}", hash))
+}
+
+/**
+ * Number of differing bits between two hashes, None if either is malformed
+ */
+pub fn distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some((a ^ b).count_ones())
+}
@@ -0,0 +1,138 @@
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::action_feedback;
+use crate::clipboard::{self, ClipboardBackend};
+use crate::db::DatabaseService;
+use crate::focus;
+use crate::history_lock::HistoryLock;
+
+/**
+ * Item shortcuts registered with the OS, by hotkey id
+ * Values are the canonical form stored in item_shortcuts (see keys::normalize_shortcut)
+ */
+pub struct ItemShortcuts {
+    registered: Mutex<HashMap<u32, String>>,
+}
+
+impl ItemShortcuts {
+    pub fn new() -> Self {
+        Self {
+            registered: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for ItemShortcuts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Register a canonical shortcut as a global hotkey
+ * Fails when the OS refuses it, e.g. because another app already holds it
+ */
+pub fn register(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let parsed: Shortcut = shortcut
+        .parse()
+        .map_err(|e| format!("Unsupported shortcut '{}': {}", shortcut, e))?;
+
+    let state = app.state::<ItemShortcuts>();
+    let mut registered = state.registered.lock();
+    if registered.contains_key(&parsed.id()) {
+        return Ok(());
+    }
+    app.global_shortcut()
+        .register(parsed)
+        .map_err(|e| format!("Could not register shortcut '{}': {}", shortcut, e))?;
+    registered.insert(parsed.id(), shortcut.to_string());
+    Ok(())
+}
+
+pub fn unregister(app: &AppHandle, shortcut: &str) {
+    let Ok(parsed) = shortcut.parse::<Shortcut>() else {
+        return;
+    };
+    let state = app.state::<ItemShortcuts>();
+    if state.registered.lock().remove(&parsed.id()).is_none() {
+        return;
+    }
+    if let Err(e) = app.global_shortcut().unregister(parsed) {
+        log::warn!("Could not unregister shortcut '{}': {}", shortcut, e);
+    }
+}
+
+/**
+ * Make the registered hotkeys match the stored bindings
+ * Runs at startup and after bindings disappear with their item (unpin, delete)
+ */
+pub fn sync(app: &AppHandle, db: &DatabaseService) {
+    let bindings: HashSet<String> = match db.get_shortcuts() {
+        Ok(shortcuts) => shortcuts.into_iter().map(|s| s.shortcut).collect(),
+        Err(e) => {
+            log::error!("Failed to load item shortcuts: {}", e);
+            return;
+        }
+    };
+
+    let stale: Vec<String> = app
+        .state::<ItemShortcuts>()
+        .registered
+        .lock()
+        .values()
+        .filter(|shortcut| !bindings.contains(*shortcut))
+        .cloned()
+        .collect();
+    for shortcut in stale {
+        unregister(app, &shortcut);
+    }
+
+    for shortcut in bindings {
+        if let Err(e) = register(app, &shortcut) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/**
+ * Global shortcut handler: paste the bound item into the focused app
+ */
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let Some(binding) = app
+        .state::<ItemShortcuts>()
+        .registered
+        .lock()
+        .get(&shortcut.id())
+        .cloned()
+    else {
+        return;
+    };
+
+    // Pasting waits for focus to settle, keep that off the event loop
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let _ = action_feedback::report(&app, "Paste shortcut", paste(&app, &binding));
+    });
+}
+
+fn paste(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    app.state::<HistoryLock>().check()?;
+    let db = app
+        .try_state::<DatabaseService>()
+        .ok_or_else(|| "Database is not initialized".to_string())?;
+    let Some(item) = db.get_shortcut_item(shortcut).map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+
+    let backend = app.state::<ClipboardBackend>();
+    clipboard::write_item(backend.provider(), &db, &item, true)?;
+    db.record_use(&item.id).map_err(|e| e.to_string())?;
+    // copyclip isn't focused when a global shortcut fires, the keystroke goes to the current app
+    focus::return_and_paste(None)
+}
@@ -0,0 +1,168 @@
+/**
+ * Modifier keys in the order they appear in a canonical shortcut
+ */
+const MODIFIERS: &[&str] = &["ctrl", "alt", "shift", "super"];
+
+/**
+ * Canonical names of non-modifier keys besides a-z, 0-9 and f1-f24
+ */
+const NAMED_KEYS: &[&str] = &[
+    "enter",
+    "escape",
+    "tab",
+    "space",
+    "backspace",
+    "delete",
+    "insert",
+    "home",
+    "end",
+    "pageup",
+    "pagedown",
+    "up",
+    "down",
+    "left",
+    "right",
+    "minus",
+    "equal",
+    "comma",
+    "period",
+    "slash",
+    "backslash",
+    "semicolon",
+    "quote",
+    "backquote",
+    "bracketleft",
+    "bracketright",
+    "capslock",
+    "printscreen",
+    "pause",
+    "menu",
+];
+
+/**
+ * Alternative spellings (lowercase, spaces/dashes/underscores removed) -> canonical name
+ * Covers platform names (Cmd, Option, Return) and DOM KeyboardEvent names
+ */
+const ALIASES: &[(&str, &str)] = &[
+    ("control", "ctrl"),
+    ("ctl", "ctrl"),
+    ("option", "alt"),
+    ("opt", "alt"),
+    ("altgr", "alt"),
+    ("cmd", "super"),
+    ("command", "super"),
+    ("meta", "super"),
+    ("win", "super"),
+    ("windows", "super"),
+    ("os", "super"),
+    ("return", "enter"),
+    ("esc", "escape"),
+    ("spacebar", "space"),
+    ("del", "delete"),
+    ("ins", "insert"),
+    ("pgup", "pageup"),
+    ("pgdn", "pagedown"),
+    ("pgdown", "pagedown"),
+    ("arrowup", "up"),
+    ("arrowdown", "down"),
+    ("arrowleft", "left"),
+    ("arrowright", "right"),
+    ("-", "minus"),
+    ("=", "equal"),
+    (",", "comma"),
+    (".", "period"),
+    ("/", "slash"),
+    ("\\", "backslash"),
+    (";", "semicolon"),
+    ("'", "quote"),
+    ("`", "backquote"),
+    ("[", "bracketleft"),
+    ("]", "bracketright"),
+    ("prtsc", "printscreen"),
+    ("printscr", "printscreen"),
+];
+
+/**
+ * Canonical name of a key, None when the key is unknown
+ * "Cmd" -> "super", "Page Up" -> "pageup", "KeyA" -> "a", "Digit1" -> "1"
+ */
+pub fn canonical_key(key: &str) -> Option<String> {
+    let key = key.trim().to_lowercase();
+    let key = if key == "-" {
+        key
+    } else {
+        key.replace([' ', '-', '_'], "")
+    };
+
+    if let Some((_, canonical)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return Some(canonical.to_string());
+    }
+    if MODIFIERS.contains(&key.as_str()) || NAMED_KEYS.contains(&key.as_str()) {
+        return Some(key);
+    }
+
+    // DOM KeyboardEvent.code names
+    let key = key
+        .strip_prefix("key")
+        .filter(|rest| rest.len() == 1)
+        .or_else(|| key.strip_prefix("digit"))
+        .unwrap_or(&key);
+
+    let is_char = key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric());
+    let is_function = key
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=24).contains(&n));
+
+    (is_char || is_function).then(|| key.to_string())
+}
+
+/**
+ * Canonical form of a shortcut: lowercase, modifiers first in a fixed order, one main key
+ * "Cmd + Shift + Page Up" -> "shift+super+pageup"
+ */
+pub fn normalize_shortcut(shortcut: &str) -> Result<String, String> {
+    let parts: Vec<&str> = shortcut
+        .split('+')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    if parts.is_empty() {
+        return Err("Shortcut is empty".to_string());
+    }
+
+    let mut modifiers = Vec::new();
+    let mut main_key = None;
+    for part in parts {
+        let key = canonical_key(part).ok_or_else(|| format!("Unknown key '{}'", part))?;
+        if MODIFIERS.contains(&key.as_str()) {
+            modifiers.push(key);
+        } else if main_key.replace(key).is_some() {
+            return Err("A shortcut can only have one non-modifier key".to_string());
+        }
+    }
+
+    let main_key = main_key.ok_or_else(|| "Shortcut needs a non-modifier key".to_string())?;
+    let mut keys: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|modifier| modifiers.iter().any(|m| m == *modifier))
+        .map(|modifier| modifier.to_string())
+        .collect();
+    keys.push(main_key);
+    Ok(keys.join("+"))
+}
+
+/**
+ * All canonical key names, for the shortcut editor
+ */
+pub fn key_names() -> Vec<String> {
+    let mut names: Vec<String> = MODIFIERS
+        .iter()
+        .chain(NAMED_KEYS)
+        .map(|name| name.to_string())
+        .collect();
+    names.extend(('a'..='z').chain('0'..='9').map(String::from));
+    names.extend((1..=24).map(|n| format!("f{}", n)));
+    names
+}
@@ -0,0 +1,170 @@
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::time::{Duration, SystemTime};
+use tauri::ipc::Channel;
+
+use crate::db::DatabaseService;
+use crate::models::ClipboardItemModel;
+
+/**
+ * Text larger than this (in KB) is stored in a file instead of the database, 0 disables
+ */
+pub const THRESHOLD_SETTING: &str = "large_item_threshold_kb";
+const DEFAULT_THRESHOLD_KB: u64 = 1024;
+
+/**
+ * Characters of a large item kept in the database for lists, search and dedup
+ */
+pub const PREVIEW_CHARS: usize = 2000;
+
+/**
+ * Bytes per chunk sent by stream_full_content
+ */
+const CHUNK_BYTES: usize = 256 * 1024;
+
+const ORPHAN_GRACE: Duration = Duration::from_secs(600);
+
+/**
+ * Full text moved out of the database; `file` is a name inside large_content_dir
+ */
+pub struct ExternalContent {
+    pub preview: String,
+    pub file: String,
+    pub size: i64,
+}
+
+/**
+ * Write content above the size threshold to a file named by its hash
+ * Identical content maps to the same file, so dedup still compares exactly
+ */
+pub fn externalize(db: &DatabaseService, content: &str) -> Result<Option<ExternalContent>, String> {
+    let threshold_kb = db
+        .get_setting(THRESHOLD_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_THRESHOLD_KB);
+
+    if threshold_kb == 0 || (content.len() as u64) <= threshold_kb.saturating_mul(1024) {
+        return Ok(None);
+    }
+
+    let dir = db.large_content_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create large items folder: {}", e))?;
+
+    let hash: String = Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let file = format!("{}.txt", hash);
+    let path = dir.join(&file);
+    if path.exists() {
+        // Same content saved before; refresh it so the orphan sweep keeps it
+        std::fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+            .map_err(|e| format!("Failed to store large item: {}", e))?;
+    } else {
+        std::fs::write(&path, content).map_err(|e| format!("Failed to store large item: {}", e))?;
+    }
+
+    Ok(Some(ExternalContent {
+        preview: content.chars().take(PREVIEW_CHARS).collect(),
+        file,
+        size: content.len() as i64,
+    }))
+}
+
+/**
+ * Full text of an item, read from its file when it was externalized
+ */
+pub fn full_content(db: &DatabaseService, item: &ClipboardItemModel) -> Result<String, String> {
+    match &item.content_file {
+        Some(file) => std::fs::read_to_string(db.large_content_dir().join(file))
+            .map_err(|e| format!("Failed to read large item: {}", e)),
+        None => Ok(item.content.clone()),
+    }
+}
+
+/**
+ * Send an item's full text over a channel in chunks, returns the bytes sent
+ * Chunks always end on a char boundary
+ */
+pub fn stream_full_content(
+    db: &DatabaseService,
+    item: &ClipboardItemModel,
+    on_chunk: &Channel<String>,
+) -> Result<u64, String> {
+    let Some(file) = &item.content_file else {
+        on_chunk
+            .send(item.content.clone())
+            .map_err(|e| e.to_string())?;
+        return Ok(item.content.len() as u64);
+    };
+
+    let mut reader = std::fs::File::open(db.large_content_dir().join(file))
+        .map_err(|e| format!("Failed to read large item: {}", e))?;
+    let mut buffer = vec![0; CHUNK_BYTES];
+    let mut pending = Vec::new();
+    let mut sent = 0;
+
+    loop {
+        let read = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buffer[..read]);
+
+        // Hold back an incomplete trailing UTF-8 sequence for the next chunk
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(format!("Large item is not valid UTF-8: {}", e)),
+        };
+        let rest = pending.split_off(valid);
+        let chunk =
+            String::from_utf8(std::mem::replace(&mut pending, rest)).map_err(|e| e.to_string())?;
+
+        sent += chunk.len() as u64;
+        on_chunk.send(chunk).map_err(|e| e.to_string())?;
+    }
+
+    if !pending.is_empty() {
+        return Err("Large item ends with a truncated character".to_string());
+    }
+    Ok(sent)
+}
+
+/**
+ * Delete large item files no longer referenced by history or archives
+ */
+pub fn sweep_orphans(db: &DatabaseService) {
+    let Ok(entries) = std::fs::read_dir(db.large_content_dir()) else {
+        return;
+    };
+    let referenced = match db.referenced_content_files() {
+        Ok(referenced) => referenced,
+        Err(e) => {
+            log::error!("Failed to list large item files: {}", e);
+            return;
+        }
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Files are written just before their item is inserted, leave fresh ones alone
+        let fresh = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age < ORPHAN_GRACE);
+        if !fresh && !referenced.contains(&name) {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                log::warn!("Failed to remove orphaned large item {}: {}", name, e);
+            }
+        }
+    }
+}
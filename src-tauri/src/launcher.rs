@@ -0,0 +1,416 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::db::DatabaseService;
+use crate::picker::fuzzy_score;
+
+/**
+ * How long the installed application list is reused before rescanning
+ */
+const SCAN_TTL: Duration = Duration::from_secs(60);
+
+/**
+ * Application found on this machine
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledApp {
+    pub name: String,
+    pub target: String, // .app bundle path (macOS), desktop file id (Linux), .lnk path (Windows)
+}
+
+/**
+ * search_apps result, aliased apps carry their alias
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppMatch {
+    pub name: String,
+    pub target: String,
+    pub alias: Option<String>,
+}
+
+/**
+ * App launcher: fuzzy search over aliases and installed applications
+ */
+pub struct AppLauncher {
+    installed: Mutex<Option<(Instant, Vec<InstalledApp>)>>,
+}
+
+impl AppLauncher {
+    pub fn new() -> Self {
+        Self {
+            installed: Mutex::new(None),
+        }
+    }
+
+    /**
+     * Installed applications, rescanned at most every SCAN_TTL
+     */
+    pub fn installed(&self) -> Vec<InstalledApp> {
+        let mut installed = self.installed.lock();
+        match installed.as_ref() {
+            Some((scanned, apps)) if scanned.elapsed() < SCAN_TTL => apps.clone(),
+            _ => {
+                let apps = scan();
+                *installed = Some((Instant::now(), apps.clone()));
+                apps
+            }
+        }
+    }
+
+    /**
+     * Best matches for `query`; an alias typed exactly always comes first
+     * An empty query lists aliases, then installed apps by name
+     */
+    pub fn search(
+        &self,
+        db: &DatabaseService,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<AppMatch>, String> {
+        let aliases = db.get_app_aliases().map_err(|e| e.to_string())?;
+        let installed = self.installed();
+        let query = query.trim().to_lowercase();
+
+        let mut scored: Vec<(i64, AppMatch)> = Vec::new();
+        for alias in aliases {
+            let name = installed
+                .iter()
+                .find(|app| app.target == alias.target)
+                .map(|app| app.name.clone())
+                .unwrap_or_else(|| display_name(&alias.target));
+
+            let score = if query.is_empty() || alias.alias.to_lowercase() == query {
+                Some(i64::MAX)
+            } else {
+                fuzzy_score(&alias.alias, &query).max(fuzzy_score(&name, &query))
+            };
+            if let Some(score) = score {
+                scored.push((
+                    score,
+                    AppMatch {
+                        name,
+                        target: alias.target,
+                        alias: Some(alias.alias),
+                    },
+                ));
+            }
+        }
+
+        for app in installed {
+            if scored.iter().any(|(_, found)| found.target == app.target) {
+                continue;
+            }
+            let score = match query.is_empty() {
+                true => Some(0),
+                false => fuzzy_score(&app.name, &query),
+            };
+            if let Some(score) = score {
+                scored.push((
+                    score,
+                    AppMatch {
+                        name: app.name,
+                        target: app.target,
+                        alias: None,
+                    },
+                ));
+            }
+        }
+
+        // Stable sort keeps aliases ahead of installed apps between equal scores
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(scored
+            .into_iter()
+            .map(|(_, found)| found)
+            .take(limit)
+            .collect())
+    }
+
+    /**
+     * Launch an alias, or a target as returned by search_apps
+     */
+    pub fn launch(
+        &self,
+        app: &AppHandle,
+        db: &DatabaseService,
+        alias_or_target: &str,
+    ) -> Result<String, String> {
+        let target = db
+            .get_app_alias_target(alias_or_target.trim())
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| alias_or_target.trim().to_string());
+        if target.is_empty() {
+            return Err("Nothing to launch".to_string());
+        }
+
+        launch_target(app, &target)?;
+        log::info!("Launched {}", target);
+        Ok(target)
+    }
+}
+
+impl Default for AppLauncher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn display_name(target: &str) -> String {
+    Path::new(target)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| target.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn scan() -> Vec<InstalledApp> {
+    let mut dirs = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/Applications/Utilities"),
+        PathBuf::from("/System/Applications"),
+        PathBuf::from("/System/Applications/Utilities"),
+    ];
+    if let Some(base) = directories::BaseDirs::new() {
+        dirs.push(base.home_dir().join("Applications"));
+    }
+
+    let mut apps: Vec<InstalledApp> = dirs
+        .iter()
+        .flat_map(|dir| entries(dir))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .map(|path| InstalledApp {
+            name: display_name(&path.to_string_lossy()),
+            target: path.display().to_string(),
+        })
+        .collect();
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+#[cfg(target_os = "linux")]
+fn scan() -> Vec<InstalledApp> {
+    let mut apps: Vec<InstalledApp> = Vec::new();
+    for dir in application_dirs() {
+        for path in entries(&dir) {
+            let Some(id) = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            // Earlier directories (the user's own) override system entries with the same id
+            if !id.ends_with(".desktop") || apps.iter().any(|app| app.target == id) {
+                continue;
+            }
+            if let Some(entry) = DesktopEntry::read(&path).filter(|entry| entry.visible) {
+                apps.push(InstalledApp {
+                    name: entry.name,
+                    target: id,
+                });
+            }
+        }
+    }
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+#[cfg(target_os = "windows")]
+fn scan() -> Vec<InstalledApp> {
+    let mut dirs = Vec::new();
+    if let Some(base) = directories::BaseDirs::new() {
+        dirs.push(
+            base.data_dir()
+                .join("Microsoft\\Windows\\Start Menu\\Programs"),
+        );
+    }
+    if let Some(program_data) = std::env::var_os("ProgramData") {
+        dirs.push(PathBuf::from(program_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+
+    let mut apps: Vec<InstalledApp> = Vec::new();
+    let mut pending = dirs;
+    while let Some(dir) = pending.pop() {
+        for path in entries(&dir) {
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+            {
+                let name = display_name(&path.to_string_lossy());
+                if !apps.iter().any(|app| app.name == name) {
+                    apps.push(InstalledApp {
+                        name,
+                        target: path.display().to_string(),
+                    });
+                }
+            }
+        }
+    }
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn scan() -> Vec<InstalledApp> {
+    Vec::new()
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn launch_target(app: &AppHandle, target: &str) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    // Bundle ids look like com.apple.Safari, anything else is a path or app name
+    let is_bundle_id = !target.contains('/') && !target.ends_with(".app") && target.contains('.');
+    if is_bundle_id {
+        let status = std::process::Command::new("open")
+            .args(["-b", target])
+            .status()
+            .map_err(|e| format!("Failed to run open: {}", e))?;
+        return match status.success() {
+            true => Ok(()),
+            false => Err(format!("No application with bundle id {}", target)),
+        };
+    }
+    if !target.contains('/') {
+        return std::process::Command::new("open")
+            .args(["-a", target])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run open: {}", e));
+    }
+    app.opener()
+        .open_path(target, None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn launch_target(_app: &AppHandle, target: &str) -> Result<(), String> {
+    let command = if target.ends_with(".desktop") {
+        let path = if Path::new(target).is_absolute() {
+            PathBuf::from(target)
+        } else {
+            application_dirs()
+                .into_iter()
+                .map(|dir| dir.join(target))
+                .find(|path| path.exists())
+                .ok_or_else(|| format!("Desktop file {} not found", target))?
+        };
+        DesktopEntry::read(&path)
+            .and_then(|entry| entry.exec)
+            .ok_or_else(|| format!("{} has no Exec line", target))?
+    } else {
+        target.to_string()
+    };
+
+    let mut child = std::process::Command::new("sh")
+        .args(["-c", &command])
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", target, e))?;
+    // Reap the process when it exits instead of leaving a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn launch_target(app: &AppHandle, target: &str) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    app.opener()
+        .open_path(target, None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn launch_target(_app: &AppHandle, _target: &str) -> Result<(), String> {
+    Err("Launching applications is not supported on this platform".to_string())
+}
+
+/**
+ * XDG application directories, most specific first
+ */
+#[cfg(target_os = "linux")]
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(base) = directories::BaseDirs::new() {
+        dirs.push(base.data_dir().join("applications"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("applications")),
+    );
+    dirs
+}
+
+#[cfg(target_os = "linux")]
+struct DesktopEntry {
+    name: String,
+    exec: Option<String>, // field codes (%f, %U, ...) removed
+    visible: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl DesktopEntry {
+    fn read(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        let mut in_entry = false;
+        let mut name = None;
+        let mut exec = None;
+        let mut visible = true;
+
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=').filter(|_| in_entry) else {
+                continue;
+            };
+            match key.trim() {
+                "Name" => name = Some(value.trim().to_string()),
+                "Exec" => exec = Some(strip_field_codes(value.trim())),
+                "Type" if value.trim() != "Application" => visible = false,
+                "NoDisplay" | "Hidden" if value.trim() == "true" => visible = false,
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            name: name?,
+            exec,
+            visible,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn strip_field_codes(exec: &str) -> String {
+    let mut out = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        // %% is a literal percent, every other code expands to nothing here
+        if let Some('%') = chars.next() {
+            out.push('%');
+        }
+    }
+    out.trim().to_string()
+}
@@ -1,43 +1,151 @@
+mod action_feedback;
+mod capabilities;
+mod capture;
+mod capture_filter;
+mod capture_health;
+mod clipboard;
+mod code_lang;
+mod color;
 mod commands;
+mod confirmation;
+mod context_capture;
 mod db;
+mod db_watcher;
+mod dedup;
+mod files;
+mod focus;
+mod highlight;
+mod history_lock;
+mod hooks;
+mod image_hash;
+mod item_shortcuts;
+mod keys;
+mod large_content;
+mod launcher;
+mod logging;
+mod maintenance;
+mod metrics;
 mod models;
+mod native_messaging;
+mod paste_format;
+mod paste_stack;
+mod picker;
+mod primary_selection;
+mod qr;
+mod query;
+mod quick_actions;
+mod recording;
+mod report;
+mod scheduler;
+mod screenshot;
+mod self_test;
+mod shared_library;
+mod shutdown;
+mod url_tracking;
+mod webhook;
+mod window_state;
 
-use db::DatabaseService;
+use capabilities::Capabilities;
+use capture_filter::CaptureFilterCounters;
+use capture_health::CaptureHealth;
+use clipboard::ClipboardBackend;
+use confirmation::Confirmations;
+use focus::FocusMemory;
+use history_lock::HistoryLock;
+use item_shortcuts::ItemShortcuts;
+use launcher::AppLauncher;
+use paste_stack::PasteStack;
+use picker::PickerSessions;
+use recording::ScreenRecorder;
+use scheduler::Scheduler;
+use shutdown::Shutdown;
 use tauri::Manager;
 
+/**
+ * Start with the main window hidden, e.g. from a login item
+ */
+const HEADLESS_FLAG: &str = "--headless";
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
+/**
+ * Directory holding the database and other app data
+ */
+pub(crate) fn app_data_dir() -> std::path::PathBuf {
+    if let Some(project_dirs) = directories::ProjectDirs::from("dev", "hasib", "copyclip") {
+        project_dirs.data_dir().to_path_buf()
+    } else {
+        // Fallback to current directory if ProjectDirs fails
+        std::env::current_dir().expect("failed to get current directory")
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logger
-    env_logger::Builder::from_default_env()
-        .format_timestamp_millis()
-        .init();
+    // Initialize logger (console, in-app ring buffer and optional log file)
+    logging::init();
+
+    // Launched by a browser as a native messaging host: serve stdin/stdout, no UI
+    if std::env::args().any(|arg| arg == native_messaging::HOST_FLAG) {
+        let result = maintenance::open_or_recover(app_data_dir().join("copyclip.db"))
+            .map_err(|e| e.to_string())
+            .and_then(|db| native_messaging::run_host(&db).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::error!("Native messaging host failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     tauri::Builder::default()
+        // Must come first: a second launch exits and shows this instance's window instead
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            if argv.iter().any(|arg| arg == HEADLESS_FLAG) {
+                return;
+            }
+            if let Err(e) = window_state::show_main(app) {
+                log::error!("Failed to show the main window: {}", e);
+            }
+        }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(item_shortcuts::handle)
+                .build(),
+        )
+        .manage(PasteStack::new())
+        .manage(ScreenRecorder::new())
+        .manage(CaptureFilterCounters::new())
+        .manage(HistoryLock::new())
+        .manage(Scheduler::new())
+        .manage(Shutdown::new())
+        .manage(PickerSessions::new())
+        .manage(Confirmations::new())
+        .manage(CaptureHealth::new())
+        .manage(AppLauncher::new())
+        .manage(Capabilities::new())
+        .manage(FocusMemory::new())
+        .manage(ItemShortcuts::new())
         .setup(|app| {
             // Initialize database on app startup
             let app_handle = app.handle();
 
-            // Get app data directory
-            let app_data_dir = if let Some(project_dirs) =
-                directories::ProjectDirs::from("dev", "hasib", "copyclip")
-            {
-                project_dirs.data_dir().to_path_buf()
-            } else {
-                // Fallback to current directory if ProjectDirs fails
-                std::env::current_dir().expect("failed to get current directory")
-            };
+            // OS clipboard unless COPYCLIP_CLIPBOARD=memory swaps in the in-memory one
+            app_handle.manage(ClipboardBackend::from_env(app_handle.clone()));
 
             // Create database path
-            let db_path = app_data_dir.join("copyclip.db");
+            let db_path = app_data_dir().join("copyclip.db");
 
             // Initialize database synchronously (rusqlite is sync)
-            match DatabaseService::new(db_path) {
+            // Corrupt databases are salvaged into a fresh file instead of failing setup
+            match maintenance::open_or_recover(db_path) {
                 Ok(db) => {
+                    logging::apply_settings(&db);
+                    shared_library::attach_all(&db);
+                    // Re-register the global hotkeys of pinned item shortcuts
+                    item_shortcuts::sync(app_handle, &db);
                     // Store database service in app state
                     app_handle.manage(db);
                     log::info!("Database initialized successfully");
@@ -47,6 +155,39 @@ pub fn run() {
                 }
             }
 
+            // Start locked when a master password is set, auto-lock when idle
+            history_lock::start(app_handle.clone());
+
+            // Probe external tools once so missing ones show up in the log
+            app_handle.state::<Capabilities>().refresh();
+
+            // Restore the main window where the user left it
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::track(&window);
+            }
+
+            // Watch the X11/Wayland PRIMARY selection when enabled in settings
+            primary_selection::start_watcher(app_handle.clone());
+
+            // Archive old items and keep history under the storage budget setting
+            maintenance::register_jobs(app_handle);
+
+            // Tell the frontend when another process changes settings or history
+            db_watcher::start(app_handle.clone());
+
+            // Notice and restart a clipboard watcher that stopped delivering copies
+            capture_health::start(app_handle.clone());
+
+            // The window starts hidden so headless mode never flashes it
+            // Headless keeps the webview (it runs the clipboard watcher), launching again shows it
+            if std::env::args().any(|arg| arg == HEADLESS_FLAG) {
+                #[cfg(target_os = "macos")]
+                app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+                log::info!("Running headless, main window hidden");
+            } else {
+                window_state::show_main(app.handle())?;
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -59,7 +200,111 @@ pub fn run() {
             commands::clear_clipboard_history,
             commands::get_clipboard_count,
             commands::load_initial_history,
+            commands::merge_clipboard_items,
+            commands::append_to_clipboard_item,
+            commands::start_paste_stack,
+            commands::stop_paste_stack,
+            commands::get_paste_stack,
+            commands::get_paste_stack_state,
+            commands::pop_paste_stack,
+            commands::reorder_paste_stack,
+            commands::clear_paste_stack,
+            commands::take_screenshot,
+            commands::start_screen_recording,
+            commands::stop_screen_recording,
+            commands::is_screen_recording,
+            commands::show_main_window,
+            commands::hide_main_window,
+            commands::get_settings,
+            commands::set_setting,
+            commands::paste_primary,
+            commands::set_clipboard_from_item,
+            commands::get_clipboard_item_files,
+            commands::get_history_timeline,
+            commands::bind_item_shortcut,
+            commands::unbind_item_shortcut,
+            commands::get_item_shortcuts,
+            commands::trigger_item_shortcut,
+            commands::get_audit_log,
+            commands::backup_database,
+            commands::restore_database,
+            commands::check_database_integrity,
+            commands::compact_database,
+            commands::get_database_storage_stats,
+            commands::get_items_page,
+            commands::stream_clipboard_items,
+            commands::get_storage_breakdown,
+            commands::get_capture_filter_stats,
+            commands::reset_capture_filter_stats,
+            commands::reset_window_state,
+            commands::get_item_qr,
+            commands::get_capture_hooks,
+            commands::set_capture_hooks,
+            commands::get_webhook_config,
+            commands::set_webhook_config,
+            commands::get_webhook_deliveries,
+            commands::install_native_messaging_host,
+            commands::uninstall_native_messaging_host,
+            commands::consolidate_duplicates,
+            commands::archive_history,
+            commands::list_archives,
+            commands::export_report,
+            commands::get_item_quick_actions,
+            commands::execute_item_quick_action,
+            commands::convert_color_item,
+            commands::get_color_swatch,
+            commands::get_history_lock_status,
+            commands::set_history_password,
+            commands::lock_history,
+            commands::unlock_history,
+            commands::get_item_context_image,
+            commands::validate_shortcut,
+            commands::get_key_names,
+            commands::get_item_full_content,
+            commands::get_jobs,
+            commands::pause_job,
+            commands::resume_job,
+            commands::run_job_now,
+            commands::get_recent_logs,
+            commands::set_log_level,
+            commands::start_picker_session,
+            commands::filter_picker,
+            commands::picker_next_page,
+            commands::picker_prev_page,
+            commands::close_picker_session,
+            commands::paste_as,
+            commands::confirm_action,
+            commands::cancel_confirmation,
+            commands::get_capture_health,
+            commands::get_usage_metrics,
+            commands::clear_usage_metrics,
+            commands::get_shared_libraries,
+            commands::add_shared_library,
+            commands::remove_shared_library,
+            commands::search_apps,
+            commands::launch_app,
+            commands::get_app_aliases,
+            commands::set_app_alias,
+            commands::delete_app_alias,
+            commands::get_platform_capabilities,
+            commands::run_self_test,
+            commands::paste_item_and_restore_focus,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Stop background threads and jobs before the process exits
+            tauri::RunEvent::Exit => app.state::<Shutdown>().run(app),
+            // Clicking the dock icon of a hidden (e.g. headless) app brings it forward
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Reopen {
+                has_visible_windows: false,
+                ..
+            } => {
+                if let Err(e) = window_state::show_main(app) {
+                    log::error!("Failed to show the main window: {}", e);
+                }
+            }
+            _ => {}
+        });
 }
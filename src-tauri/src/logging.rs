@@ -0,0 +1,265 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::db::DatabaseService;
+
+/**
+ * Most verbose level recorded (error, warn, info, debug, trace or off)
+ */
+pub const LEVEL_SETTING: &str = "log_level";
+/**
+ * Also write records to rotating files in the logs folder when "true"
+ */
+pub const FILE_SETTING: &str = "log_file_enabled";
+
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/**
+ * Records kept in memory for get_recent_logs
+ */
+const BUFFER_CAPACITY: usize = 2000;
+
+/**
+ * Size at which copyclip.log is rotated, and how many rotated files are kept
+ */
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const ROTATED_FILES: usize = 3;
+
+/**
+ * One captured log record
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: i64, // ms timestamp
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/**
+ * Logger feeding the console (filtered by RUST_LOG as before), the ring buffer and the log file
+ */
+struct AppLogger {
+    console: env_logger::Logger,
+    buffer: Mutex<VecDeque<LogEntry>>,
+    file: Mutex<Option<LogFile>>,
+}
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if self.console.matches(record) {
+            self.console.log(record);
+        }
+
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+
+        // Logging can't report its own failures, a failed write just drops the line
+        let _ = write_line(&mut self.file.lock(), &entry);
+
+        let mut buffer = self.buffer.lock();
+        if buffer.len() == BUFFER_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(file) = self.file.lock().as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/**
+ * Install the logger; settings are applied once the database is open
+ */
+pub fn init() {
+    let logger = LOGGER.get_or_init(|| AppLogger {
+        console: env_logger::Builder::from_default_env()
+            .format_timestamp_millis()
+            .build(),
+        buffer: Mutex::new(VecDeque::with_capacity(BUFFER_CAPACITY)),
+        file: Mutex::new(None),
+    });
+
+    if log::set_logger(logger).is_ok() {
+        set_max_level(DEFAULT_LEVEL);
+    }
+}
+
+/**
+ * RUST_LOG can still raise the level for console debugging
+ */
+fn set_max_level(level: LevelFilter) {
+    let console = LOGGER
+        .get()
+        .map_or(LevelFilter::Off, |logger| logger.console.filter());
+    log::set_max_level(level.max(console));
+}
+
+/**
+ * Apply the level and log file settings
+ */
+pub fn apply_settings(db: &DatabaseService) {
+    let level = db
+        .get_setting(LEVEL_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| LevelFilter::from_str(&value).ok())
+        .unwrap_or(DEFAULT_LEVEL);
+    set_max_level(level);
+
+    let file_enabled = db.get_setting(FILE_SETTING).ok().flatten().as_deref() == Some("true");
+    if let Some(logger) = LOGGER.get() {
+        let mut file = logger.file.lock();
+        if !file_enabled {
+            *file = None;
+        } else if file.is_none() {
+            match open_log_file() {
+                Ok(opened) => *file = Some(opened),
+                Err(e) => {
+                    drop(file);
+                    log::error!("Failed to open log file: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Whether a setting is applied by apply_settings
+ */
+pub fn is_logging_setting(key: &str) -> bool {
+    key == LEVEL_SETTING || key == FILE_SETTING
+}
+
+/**
+ * Store and apply a new level
+ */
+pub fn set_level(db: &DatabaseService, level: &str) -> Result<(), String> {
+    let level = LevelFilter::from_str(level.trim())
+        .map_err(|_| format!("Unknown log level '{}'", level))?;
+    db.set_setting(LEVEL_SETTING, &level.to_string().to_lowercase())
+        .map_err(|e| e.to_string())?;
+    set_max_level(level);
+    log::info!("Log level set to {}", level);
+    Ok(())
+}
+
+/**
+ * Buffered records at `level` or more severe, oldest first, at most `limit` of the newest
+ */
+pub fn recent(level: Option<&str>, limit: usize) -> Result<Vec<LogEntry>, String> {
+    let level = match level {
+        Some(level) => Some(
+            Level::from_str(level.trim()).map_err(|_| format!("Unknown log level '{}'", level))?,
+        ),
+        None => None,
+    };
+    let Some(logger) = LOGGER.get() else {
+        return Ok(Vec::new());
+    };
+
+    let buffer = logger.buffer.lock();
+    let mut entries: Vec<LogEntry> = buffer
+        .iter()
+        .rev()
+        .filter(|entry| {
+            level.is_none_or(|level| {
+                Level::from_str(&entry.level).is_ok_and(|entry_level| entry_level <= level)
+            })
+        })
+        .take(limit)
+        .cloned()
+        .collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+fn log_dir() -> PathBuf {
+    crate::app_data_dir().join("logs")
+}
+
+fn open_log_file() -> std::io::Result<LogFile> {
+    std::fs::create_dir_all(log_dir())?;
+    let file = File::options()
+        .create(true)
+        .append(true)
+        .open(log_dir().join("copyclip.log"))?;
+    let size = file.metadata()?.len();
+    Ok(LogFile { file, size })
+}
+
+fn write_line(slot: &mut Option<LogFile>, entry: &LogEntry) -> std::io::Result<()> {
+    if slot
+        .as_ref()
+        .is_some_and(|file| file.size >= MAX_FILE_BYTES)
+    {
+        // Close before renaming, Windows can't rename an open file
+        *slot = None;
+        rotate()?;
+        *slot = Some(open_log_file()?);
+    }
+    let Some(log_file) = slot.as_mut() else {
+        return Ok(());
+    };
+
+    let time = chrono::DateTime::from_timestamp_millis(entry.timestamp)
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default();
+    let line = format!(
+        "{} {:<5} {} {}\n",
+        time, entry.level, entry.target, entry.message
+    );
+    log_file.file.write_all(line.as_bytes())?;
+    log_file.size += line.len() as u64;
+    Ok(())
+}
+
+/**
+ * copyclip.log -> copyclip.log.1 -> ... -> copyclip.log.N, dropping the oldest
+ */
+fn rotate() -> std::io::Result<()> {
+    let dir = log_dir();
+    let path = |n: usize| match n {
+        0 => dir.join("copyclip.log"),
+        n => dir.join(format!("copyclip.log.{}", n)),
+    };
+    if path(ROTATED_FILES).exists() {
+        std::fs::remove_file(path(ROTATED_FILES))?;
+    }
+    for n in (0..ROTATED_FILES).rev() {
+        if path(n).exists() {
+            std::fs::rename(path(n), path(n + 1))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,321 @@
+use rusqlite::{Connection, ErrorCode};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::DatabaseService;
+use crate::large_content;
+use crate::models::{ArchiveReportModel, CompactReportModel};
+use crate::scheduler::Scheduler;
+
+/**
+ * Free pages above which deletes trigger an incremental vacuum
+ */
+pub const AUTO_VACUUM_SETTING: &str = "auto_vacuum_free_pages";
+const DEFAULT_AUTO_VACUUM_FREE_PAGES: i64 = 1024;
+
+/**
+ * Maximum history size in MB; unset or 0 means unlimited
+ */
+pub const STORAGE_BUDGET_SETTING: &str = "storage_budget_mb";
+
+/**
+ * Months after which unpinned items move to archive files; unset or 0 disables archiving
+ */
+pub const ARCHIVE_AFTER_MONTHS_SETTING: &str = "archive_after_months";
+
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const HOUSEKEEPING_JITTER: Duration = Duration::from_secs(30);
+
+/**
+ * Tables salvaged during recovery, parents before children
+ */
+const SALVAGE_TABLES: &[&str] = &[
+    "clipboard_items",
+    "clipboard_files",
+    "item_context_images",
+    "item_shortcuts",
+    "app_aliases",
+    "settings",
+    "audit_log",
+    "webhook_deliveries",
+    "usage_metrics",
+];
+
+/**
+ * Open the database, recovering from corruption instead of failing startup
+ * A corrupt file is moved aside and its readable rows are copied into a fresh database
+ * Other errors (busy, read-only, can't open) are returned: the app and the native
+ * messaging host share the file, moving it aside there would split the history
+ */
+pub fn open_or_recover(db_path: PathBuf) -> Result<DatabaseService, Box<dyn std::error::Error>> {
+    match DatabaseService::new(db_path.clone()) {
+        Ok(db) => match db.check_integrity(false) {
+            Ok(problems) if problems.is_empty() => return Ok(db),
+            Ok(problems) => {
+                log::error!("Database integrity check failed: {:?}", problems);
+            }
+            Err(e) if is_corruption(&e) => {
+                log::error!("Database integrity check hit corruption: {}", e);
+            }
+            Err(e) => return Err(e.into()),
+        },
+        Err(e) if db_path.exists() && is_corruption(e.as_ref()) => {
+            log::error!("Failed to open database, attempting recovery: {}", e);
+        }
+        Err(e) => return Err(e),
+    }
+
+    let corrupt_path = db_path.with_extension(format!(
+        "db.corrupt-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    move_aside(&db_path, &corrupt_path)?;
+
+    let db = DatabaseService::new(db_path)?;
+    let salvaged = db.with_connection(|conn| salvage(conn, &corrupt_path))?;
+    log::warn!(
+        "Recovered database: salvaged {} rows, corrupt copy kept at {}",
+        salvaged,
+        corrupt_path.display()
+    );
+
+    Ok(db)
+}
+
+/**
+ * Whether an error means the file itself is damaged, as opposed to locked or unreadable
+ */
+fn is_corruption(error: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(
+        error
+            .downcast_ref::<rusqlite::Error>()
+            .and_then(|e| e.sqlite_error_code()),
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+/**
+ * Move the database and its WAL/journal side files out of the way
+ */
+fn move_aside(db_path: &Path, corrupt_path: &Path) -> std::io::Result<()> {
+    std::fs::rename(db_path, corrupt_path)?;
+
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let side = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+        if side.exists() {
+            let _ = std::fs::rename(&side, format!("{}{}", corrupt_path.display(), suffix));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Copy every readable row from the corrupt file into the fresh database
+ */
+fn salvage(conn: &Connection, corrupt_path: &Path) -> rusqlite::Result<usize> {
+    conn.execute(
+        "ATTACH DATABASE ? AS corrupt",
+        rusqlite::params![corrupt_path.to_string_lossy()],
+    )?;
+
+    let mut salvaged = 0;
+    for table in SALVAGE_TABLES {
+        match salvage_table(conn, table) {
+            Ok(rows) => salvaged += rows,
+            Err(e) => log::error!("Could not salvage table {}: {}", table, e),
+        }
+    }
+
+    conn.execute("DETACH DATABASE corrupt", [])?;
+    Ok(salvaged)
+}
+
+fn salvage_table(conn: &Connection, table: &str) -> rusqlite::Result<usize> {
+    // Only copy columns present in both schemas
+    let columns: Vec<String> = conn
+        .prepare(&format!(
+            "SELECT name FROM pragma_table_info('{table}') WHERE name IN (SELECT name FROM corrupt.pragma_table_info('{table}'))"
+        ))?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    if columns.is_empty() {
+        return Ok(0);
+    }
+    let columns = columns.join(", ");
+
+    // Fast path: the whole table is readable
+    let bulk = format!(
+        "INSERT OR IGNORE INTO main.{table} ({columns}) SELECT {columns} FROM corrupt.{table}"
+    );
+    if let Ok(rows) = conn.execute(&bulk, []) {
+        return Ok(rows);
+    }
+
+    // Slow path: copy row by row, skipping unreadable pages
+    let rowids: Vec<i64> = conn
+        .prepare(&format!("SELECT rowid FROM corrupt.{table}"))?
+        .query_map([], |row| row.get(0))?
+        .filter_map(Result::ok)
+        .collect();
+
+    let single = format!("{bulk} WHERE rowid = ?");
+    let rows = rowids
+        .into_iter()
+        .filter_map(|rowid| conn.execute(&single, rusqlite::params![rowid]).ok())
+        .sum();
+
+    Ok(rows)
+}
+
+/**
+ * VACUUM the database and report the size change
+ */
+pub fn compact(db: &DatabaseService) -> rusqlite::Result<CompactReportModel> {
+    let size_before = db.storage_stats()?.size_bytes;
+    db.vacuum()?;
+    let size_after = db.storage_stats()?.size_bytes;
+
+    Ok(CompactReportModel {
+        size_before,
+        size_after,
+        reclaimed_bytes: size_before - size_after,
+    })
+}
+
+/**
+ * Run an incremental vacuum when free pages exceed the configured threshold
+ * A threshold of 0 disables automatic reclamation
+ */
+pub fn reclaim_if_needed(db: &DatabaseService) {
+    let threshold = db
+        .get_setting(AUTO_VACUUM_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_AUTO_VACUUM_FREE_PAGES);
+
+    if threshold <= 0 {
+        return;
+    }
+
+    match db.storage_stats() {
+        Ok(stats) if stats.freelist_count > threshold => {
+            if let Err(e) = db.incremental_vacuum() {
+                log::error!("Incremental vacuum failed: {}", e);
+            } else {
+                log::info!("Reclaimed {} free pages", stats.freelist_count);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to read storage stats: {}", e),
+    }
+}
+
+/**
+ * Register the housekeeping jobs: archive old items, evict history while over the
+ * storage budget and remove orphaned large item files
+ */
+pub fn register_jobs(app: &AppHandle) {
+    let scheduler = app.state::<Scheduler>();
+    let register = |name: &str, run: fn(&DatabaseService)| {
+        scheduler.register(
+            app,
+            name,
+            HOUSEKEEPING_INTERVAL,
+            HOUSEKEEPING_JITTER,
+            move |_, db| {
+                run(db);
+                Ok(())
+            },
+        );
+    };
+    register("archive", archive_old_items);
+    register("storage_budget", enforce_storage_budget);
+    register("large_items_sweep", large_content::sweep_orphans);
+}
+
+/**
+ * Evict the oldest unpinned items until history is under the configured budget
+ */
+pub fn enforce_storage_budget(db: &DatabaseService) {
+    let budget_mb = db
+        .get_setting(STORAGE_BUDGET_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(0);
+
+    if budget_mb <= 0 {
+        return;
+    }
+
+    match db.evict_to_budget(budget_mb * 1024 * 1024) {
+        Ok(0) => {}
+        Ok(evicted) => {
+            log::info!(
+                "Evicted {} items to stay under the {} MB storage budget",
+                evicted,
+                budget_mb
+            );
+            let detail = format!("budget {} MB", budget_mb);
+            if let Err(e) = db.record_audit("storage_budget", Some(&detail), evicted) {
+                log::error!("Failed to record audit entry 'storage_budget': {}", e);
+            }
+            reclaim_if_needed(db);
+        }
+        Err(e) => log::error!("Failed to enforce storage budget: {}", e),
+    }
+}
+
+/**
+ * Move items older than the archive_after_months setting into archive files
+ */
+pub fn archive_old_items(db: &DatabaseService) {
+    let months = db
+        .get_setting(ARCHIVE_AFTER_MONTHS_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    if months == 0 {
+        return;
+    }
+
+    match archive_older_than(db, months) {
+        Ok(report) if report.archived > 0 => {
+            log::info!(
+                "Archived {} items into {} monthly files",
+                report.archived,
+                report.months.len()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to archive old items: {}", e),
+    }
+}
+
+/**
+ * Archive unpinned items older than `months`, recording the move in the audit log
+ */
+pub fn archive_older_than(
+    db: &DatabaseService,
+    months: u32,
+) -> Result<ArchiveReportModel, Box<dyn std::error::Error>> {
+    let cutoff = chrono::Utc::now()
+        .checked_sub_months(chrono::Months::new(months))
+        .ok_or("Archive cutoff out of range")?
+        .timestamp_millis();
+
+    let report = db.archive_items_before(cutoff)?;
+    if report.archived > 0 {
+        let detail = format!("older than {} months", months);
+        if let Err(e) = db.record_audit("archive", Some(&detail), report.archived) {
+            log::error!("Failed to record audit entry 'archive': {}", e);
+        }
+        reclaim_if_needed(db);
+    }
+    Ok(report)
+}
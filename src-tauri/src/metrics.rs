@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::db::DatabaseService;
+
+/**
+ * Count captures, pastes and actions locally ("true" to enable, off by default)
+ * Nothing is ever sent anywhere, the counters only feed get_usage_metrics
+ */
+pub const ENABLED_SETTING: &str = "usage_metrics_enabled";
+
+const DEFAULT_DAYS: u32 = 30;
+
+/**
+ * Something worth counting
+ */
+pub enum Metric<'a> {
+    Capture,
+    Paste,
+    Action(&'a str),
+}
+
+impl Metric<'_> {
+    fn key(&self) -> String {
+        match self {
+            Metric::Capture => "capture".to_string(),
+            Metric::Paste => "paste".to_string(),
+            Metric::Action(name) => format!("action:{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub day: String, // local date, YYYY-MM-DD
+    pub captures: i64,
+    pub pastes: i64,
+    pub actions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionUsage {
+    pub action: String,
+    pub count: i64,
+    pub average_ms: f64,
+}
+
+/**
+ * Usage over the requested window, returned by get_usage_metrics
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageMetrics {
+    pub enabled: bool,
+    pub since: String,
+    pub days: Vec<DailyUsage>,     // only days with activity, oldest first
+    pub actions: Vec<ActionUsage>, // most used first
+}
+
+pub fn enabled(db: &DatabaseService) -> bool {
+    db.get_setting(ENABLED_SETTING).ok().flatten().as_deref() == Some("true")
+}
+
+/**
+ * Count one occurrence when metrics are enabled; `elapsed` is the action's latency
+ */
+pub fn record(db: &DatabaseService, metric: Metric, elapsed: Option<Duration>) {
+    if !enabled(db) {
+        return;
+    }
+
+    let elapsed_ms = elapsed.map_or(0, |elapsed| elapsed.as_millis() as i64);
+    if let Err(e) = db.record_usage(&today(), &metric.key(), elapsed_ms) {
+        log::warn!("Failed to record usage metric '{}': {}", metric.key(), e);
+    }
+}
+
+/**
+ * Daily totals and per-action latency for the last `days` days (30 by default)
+ */
+pub fn summary(db: &DatabaseService, days: Option<u32>) -> Result<UsageMetrics, String> {
+    let days = days.unwrap_or(DEFAULT_DAYS).max(1);
+    let since = (chrono::Local::now().date_naive() - chrono::Days::new(u64::from(days - 1)))
+        .format("%Y-%m-%d")
+        .to_string();
+    let counters = db.get_usage_counters(&since).map_err(|e| e.to_string())?;
+
+    let mut daily: BTreeMap<String, DailyUsage> = BTreeMap::new();
+    let mut actions: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for counter in counters {
+        let day = daily
+            .entry(counter.day.clone())
+            .or_insert_with(|| DailyUsage {
+                day: counter.day.clone(),
+                ..Default::default()
+            });
+
+        match counter.metric.as_str() {
+            "capture" => day.captures += counter.count,
+            "paste" => day.pastes += counter.count,
+            metric => {
+                if let Some(action) = metric.strip_prefix("action:") {
+                    day.actions += counter.count;
+                    let totals = actions.entry(action.to_string()).or_default();
+                    totals.0 += counter.count;
+                    totals.1 += counter.total_ms;
+                }
+            }
+        }
+    }
+
+    let mut actions: Vec<ActionUsage> = actions
+        .into_iter()
+        .map(|(action, (count, total_ms))| ActionUsage {
+            action,
+            count,
+            average_ms: total_ms as f64 / count.max(1) as f64,
+        })
+        .collect();
+    actions.sort_by_key(|action| std::cmp::Reverse(action.count));
+
+    Ok(UsageMetrics {
+        enabled: enabled(db),
+        since,
+        days: daily.into_values().collect(),
+        actions,
+    })
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
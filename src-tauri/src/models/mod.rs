@@ -1,6 +1,14 @@
+use crate::code_lang;
+use crate::color::Color;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+/**
+ * Where an item was captured from, see ClipboardItemModel::source
+ */
+pub const SOURCE_CLIPBOARD: &str = "clipboard";
+pub const SOURCE_PRIMARY_SELECTION: &str = "primary_selection";
+
 /**
  * ClipboardItem entity - represents a clipboard history item in the database
  */
@@ -15,6 +23,23 @@ pub struct ClipboardItemModel {
     pub file_paths: Option<String>, // JSON array
     pub created_at: i64,
     pub updated_at: i64,
+    pub html: Option<String>, // rich text flavors captured alongside plain text
+    pub rtf: Option<String>,
+    pub image_hash: Option<String>, // perceptual hash for image dedup
+    pub use_count: i64,             // times pasted back from history
+    pub source_url: Option<String>, // page the copy came from (browser extension)
+    pub source_title: Option<String>,
+    pub color: Option<String>, // normalized hex when the text is a color value
+    pub language: Option<String>, // guessed programming language of code snippets
+    pub content_file: Option<String>, // full text file when content holds only a preview
+    pub content_size: Option<i64>, // full text size in bytes for externalized items
+    pub original_content: Option<String>, // text as copied, when URL tracking was stripped
+    #[serde(default = "default_source")]
+    pub source: String, // SOURCE_*; items are only deduplicated against the same source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>, // read-only shared library the item comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchHighlightModel>, // search matches, only set in search results
 }
 
 impl ClipboardItemModel {
@@ -26,6 +51,8 @@ impl ClipboardItemModel {
         file_paths: Option<String>,
     ) -> Self {
         let now = Utc::now().timestamp_millis();
+        let color = Color::parse(&content).map(Color::to_hex);
+        let language = code_lang::detect(&content).map(str::to_string);
         Self {
             id,
             content,
@@ -36,18 +63,270 @@ impl ClipboardItemModel {
             file_paths,
             created_at: now,
             updated_at: now,
+            html: None,
+            rtf: None,
+            image_hash: None,
+            use_count: 0,
+            source_url: None,
+            source_title: None,
+            color,
+            language,
+            content_file: None,
+            content_size: None,
+            original_content: None,
+            source: SOURCE_CLIPBOARD.to_string(),
+            library: None,
+            highlight: None,
         }
     }
 }
 
+fn default_source() -> String {
+    SOURCE_CLIPBOARD.to_string()
+}
+
+/**
+ * File referenced by a 'file' clipboard item
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardFileModel {
+    pub item_id: String,
+    pub path: String,
+    pub size: Option<i64>,
+    pub mime: Option<String>,
+    pub exists: bool, // false once the file was moved or deleted
+}
+
+/**
+ * Screenshot of the window an item was copied from
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemContextImageModel {
+    pub item_id: String,
+    pub image_base64: String, // PNG, longest side at most 640px
+    pub width: u32,
+    pub height: u32,
+    pub captured_at: i64,
+}
+
+/**
+ * Shortcut (hotkey or gamepad chord) bound to a pinned item
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemShortcutModel {
+    pub shortcut: String, // normalized, e.g. "Ctrl+Alt+1"
+    pub item_id: String,
+    pub created_at: i64,
+}
+
+/**
+ * User-defined alias for launching an application
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppAliasModel {
+    pub alias: String,
+    pub target: String, // .app bundle / bundle id (macOS), desktop file id (Linux), exe or .lnk path (Windows)
+    pub created_at: i64,
+}
+
+/**
+ * Audit log entry for a destructive operation
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogEntryModel {
+    pub id: i64,
+    pub operation: String, // 'delete', 'clear', 'evict', ...
+    pub detail: Option<String>,
+    pub affected: i64,
+    pub created_at: i64,
+}
+
+/**
+ * Outcome of one webhook delivery (after all retries)
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryModel {
+    pub id: i64,
+    pub item_id: String,
+    pub url: String,
+    pub delivered: bool,
+    pub attempts: i64,
+    pub http_status: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: i64,
+}
+
+/**
+ * One day's counter from the opt-in local usage metrics
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageCounterModel {
+    pub day: String,    // local date, YYYY-MM-DD
+    pub metric: String, // 'capture', 'paste' or 'action:<name>'
+    pub count: i64,
+    pub total_ms: i64, // summed latency, 0 for untimed metrics
+}
+
+/**
+ * Database file page usage
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageStatsModel {
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    pub size_bytes: i64,
+    pub free_bytes: i64,
+}
+
+/**
+ * History disk usage for one item type
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageBreakdownModel {
+    pub item_type: String,
+    pub item_count: i64,
+    pub pinned_count: i64,
+    pub text_bytes: i64, // content (full size when externalized) plus html/rtf flavors
+    pub image_bytes: i64, // base64 image data
+    pub total_bytes: i64,
+}
+
+/**
+ * Result of a compact_database run
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactReportModel {
+    pub size_before: i64,
+    pub size_after: i64,
+    pub reclaimed_bytes: i64,
+}
+
+/**
+ * Result of a consolidate_duplicates run
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateReportModel {
+    pub groups: usize, // duplicate groups found
+    pub merged: usize, // rows removed into their group's representative
+}
+
+/**
+ * Monthly archive database file
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveFileModel {
+    pub month: String, // YYYY-MM
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/**
+ * Result of an archive run
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveReportModel {
+    pub archived: usize,
+    pub months: Vec<String>,
+}
+
+/**
+ * Lightweight list row without heavy columns (image data, rich flavors, full content)
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardItemSummaryModel {
+    pub id: String,
+    pub preview: String, // first PREVIEW_CHARS characters of content
+    pub content_length: i64,
+    pub content_size: i64, // bytes of the full text, including externalized content
+    pub is_truncated: bool, // preview only, load the rest with get_item_full_content
+    pub item_type: String,
+    pub is_pinned: bool,
+    pub has_image: bool,
+    pub timestamp: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub language: Option<String>,
+    pub highlight: Option<SearchHighlightModel>, // set when the page was searched
+}
+
+/**
+ * Snippet around the first search match, split into matched and plain runs
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHighlightModel {
+    pub segments: Vec<HighlightSegmentModel>,
+    pub truncated_start: bool, // content continues before the snippet
+    pub truncated_end: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighlightSegmentModel {
+    pub text: String,
+    pub matched: bool,
+}
+
+/**
+ * One page of summaries plus the cursor for the next page
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardItemPageModel {
+    pub items: Vec<ClipboardItemSummaryModel>,
+    pub next_cursor: Option<String>, // opaque, None on the last page
+}
+
+/**
+ * Current page of a picker session's ranked matches
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickerPageModel {
+    pub session_id: String,
+    pub query: String,
+    pub page: usize, // 0-based
+    pub total_matches: usize,
+    pub has_prev: bool,
+    pub has_next: bool,
+    pub items: Vec<ClipboardItemSummaryModel>,
+}
+
+/**
+ * Sort order for history queries
+ * Pinned items always stay on top
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    Newest,
+    Oldest,
+    MostUsed,
+    Largest,
+    Alphabetical,
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
 /**
  * Database-agnostic query filters
  */
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ClipboardQueryFilter {
     pub search: Option<String>,
     pub item_type: Option<String>,
+    pub language: Option<String>, // code snippets tagged with this language
     pub is_pinned: Option<bool>,
+    pub since: Option<i64>, // inclusive, ms timestamp
+    pub until: Option<i64>, // exclusive, ms timestamp
+    pub sort_by: SortBy,
+    pub sort_direction: Option<SortDirection>, // None uses the sort's natural direction
+    pub include_archives: bool,                // also search monthly archive files
+    pub include_shared: bool,                  // also search attached shared libraries
     pub limit: u64,
     pub offset: u64,
 }
@@ -57,9 +336,37 @@ impl Default for ClipboardQueryFilter {
         Self {
             search: None,
             item_type: None,
+            language: None,
             is_pinned: None,
+            since: None,
+            until: None,
+            sort_by: SortBy::Newest,
+            sort_direction: None,
+            include_archives: false,
+            include_shared: false,
             limit: 50,
             offset: 0,
         }
     }
 }
+
+/**
+ * Timeline grouping granularity
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineGrouping {
+    Day,
+    Week,
+}
+
+/**
+ * Item count for one day/week of history
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineBucketModel {
+    pub period: String, // "2024-05-01" or "2024-W18" in local time
+    pub count: i64,
+    pub first_timestamp: i64,
+    pub last_timestamp: i64,
+}
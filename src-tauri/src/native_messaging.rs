@@ -0,0 +1,419 @@
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::capture::{self, CapturedItem, Stored};
+use crate::db::DatabaseService;
+use crate::history_lock;
+use crate::models::{ClipboardItemModel, ClipboardQueryFilter};
+use crate::webhook;
+
+/**
+ * Version of the message schema below; bump on incompatible changes
+ */
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/**
+ * Name the browser extension connects to (chrome.runtime.connectNative)
+ */
+pub const HOST_NAME: &str = "dev.hasib.copyclip";
+
+/**
+ * Command-line flag the manifest wrapper passes to start host mode
+ */
+pub const HOST_FLAG: &str = "--native-messaging";
+
+/**
+ * Chrome rejects host messages over 1 MB
+ */
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+const MAX_QUERY_LIMIT: u64 = 100;
+
+const PROTECTED_ERROR: &str =
+    "History is protected by a master password and can't be read from the browser";
+
+/**
+ * Message from the browser extension
+ */
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostRequest {
+    Hello,
+    PushCopy {
+        text: String,
+        html: Option<String>,
+        url: Option<String>,
+        title: Option<String>,
+    },
+    QueryHistory {
+        search: Option<String>,
+        limit: Option<u64>,
+    },
+    GetItem {
+        id: String,
+    },
+}
+
+/**
+ * Reply sent for every request
+ */
+#[derive(Debug, Serialize)]
+pub struct HostResponse {
+    pub version: u32,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HostResponse {
+    fn ok(result: serde_json::Value) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            ok: true,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn error(error: String) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            ok: false,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/**
+ * Serve native messages on stdin/stdout until the browser closes the pipe
+ */
+pub fn run_host(db: &DatabaseService) -> io::Result<()> {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    let mut saved = Vec::new();
+    while let Some(message) = read_message(&mut stdin)? {
+        let response = match serde_json::from_slice::<HostRequest>(&message) {
+            Ok(request) => handle(db, request, &mut saved),
+            Err(e) => HostResponse::error(format!("Invalid message: {}", e)),
+        };
+        write_message(&mut stdout, &response)?;
+
+        // Webhook retries can take a while, the extension already has its reply
+        for item in saved.drain(..) {
+            webhook::notify_blocking(db, &item);
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Read one length-prefixed message, None on EOF
+ */
+fn read_message(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let mut message = vec![0u8; u32::from_ne_bytes(length) as usize];
+    reader.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+fn write_message(writer: &mut impl Write, response: &HostResponse) -> io::Result<()> {
+    let mut body = serde_json::to_vec(response)?;
+    if body.len() > MAX_RESPONSE_BYTES {
+        body = serde_json::to_vec(&HostResponse::error(
+            "Response too large, request fewer items".to_string(),
+        ))?;
+    }
+
+    writer.write_all(&(body.len() as u32).to_ne_bytes())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+fn handle(
+    db: &DatabaseService,
+    request: HostRequest,
+    saved: &mut Vec<ClipboardItemModel>,
+) -> HostResponse {
+    let result = match request {
+        HostRequest::Hello => Ok(serde_json::json!({
+            "name": HOST_NAME,
+            "app_version": env!("CARGO_PKG_VERSION"),
+        })),
+        HostRequest::PushCopy {
+            text,
+            html,
+            url,
+            title,
+        } => push_copy(db, text, html, url, title, saved),
+        // The unlock state lives in the app, the extension has no way to enter the password
+        HostRequest::QueryHistory { .. } | HostRequest::GetItem { .. }
+            if history_lock::has_password(db) =>
+        {
+            Err(PROTECTED_ERROR.to_string())
+        }
+        HostRequest::QueryHistory { search, limit } => {
+            let filter = ClipboardQueryFilter {
+                search,
+                limit: limit.unwrap_or(20).min(MAX_QUERY_LIMIT),
+                ..Default::default()
+            };
+            db.get_items(filter)
+                .map_err(|e| e.to_string())
+                .and_then(|items| serde_json::to_value(items).map_err(|e| e.to_string()))
+        }
+        HostRequest::GetItem { id } => db
+            .get_item(&id)
+            .map_err(|e| e.to_string())
+            .and_then(|item| serde_json::to_value(item).map_err(|e| e.to_string())),
+    };
+
+    match result {
+        Ok(value) => HostResponse::ok(value),
+        Err(e) => HostResponse::error(e),
+    }
+}
+
+/**
+ * Save a copy made in the browser together with its page context
+ * Goes through the same filters, hooks and dedup as clipboard captures
+ */
+fn push_copy(
+    db: &DatabaseService,
+    text: String,
+    html: Option<String>,
+    url: Option<String>,
+    title: Option<String>,
+    saved: &mut Vec<ClipboardItemModel>,
+) -> Result<serde_json::Value, String> {
+    if text.trim().is_empty() {
+        return Err("Copied text is empty".to_string());
+    }
+    let mut captured = CapturedItem::text(text);
+    captured.html = html;
+    captured.source_url = url;
+    captured.source_title = title;
+
+    match capture::store(db, captured)? {
+        Stored::Saved(item) => {
+            let id = item.id.clone();
+            saved.push(*item);
+            Ok(serde_json::json!({ "saved": true, "id": id }))
+        }
+        Stored::Duplicate { bumped, .. } => {
+            Ok(serde_json::json!({ "saved": false, "bumped": bumped }))
+        }
+        Stored::Filtered(reason) => {
+            Ok(serde_json::json!({ "saved": false, "filtered": format!("{:?}", reason) }))
+        }
+    }
+}
+
+/**
+ * Browsers the host manifest can be installed for
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Brave,
+    Edge,
+    Firefox,
+}
+
+/**
+ * Install the host manifest (and launcher script) for a browser extension
+ * Returns the manifest path
+ */
+pub fn install(browser: Browser, extension_id: &str, data_dir: &Path) -> Result<PathBuf, String> {
+    if extension_id.trim().is_empty() {
+        return Err("Extension id is required".to_string());
+    }
+
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let launcher = write_launcher(&exe, data_dir)?;
+
+    let mut manifest = serde_json::json!({
+        "name": HOST_NAME,
+        "description": "Copyclip clipboard history",
+        "path": launcher,
+        "type": "stdio",
+    });
+    if browser == Browser::Firefox {
+        manifest["allowed_extensions"] = serde_json::json!([extension_id]);
+    } else {
+        manifest["allowed_origins"] =
+            serde_json::json!([format!("chrome-extension://{}/", extension_id)]);
+    }
+
+    let path = manifest_path(browser, data_dir)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+
+    register(browser, &path)?;
+    log::info!("Installed native messaging host for {:?}", browser);
+    Ok(path)
+}
+
+/**
+ * Remove the host manifest for a browser
+ */
+pub fn uninstall(browser: Browser, data_dir: &Path) -> Result<bool, String> {
+    let path = manifest_path(browser, data_dir)?;
+    unregister(browser)?;
+
+    if !path.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Manifests cannot pass arguments, so point them at a script adding HOST_FLAG
+ */
+fn write_launcher(exe: &Path, data_dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(data_dir).map_err(|e| e.to_string())?;
+
+    #[cfg(windows)]
+    let (path, script) = (
+        data_dir.join("native-messaging-host.bat"),
+        format!("@echo off\r\n\"{}\" {} %*\r\n", exe.display(), HOST_FLAG),
+    );
+    #[cfg(not(windows))]
+    let (path, script) = (
+        data_dir.join("native-messaging-host.sh"),
+        format!(
+            "#!/bin/sh\nexec \"{}\" {} \"$@\"\n",
+            exe.display(),
+            HOST_FLAG
+        ),
+    );
+
+    std::fs::write(&path, script).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+#[cfg(target_os = "linux")]
+fn manifest_path(browser: Browser, _data_dir: &Path) -> Result<PathBuf, String> {
+    let home = directories::BaseDirs::new()
+        .ok_or("Home directory not found")?
+        .home_dir()
+        .to_path_buf();
+    let dir = match browser {
+        Browser::Chrome => ".config/google-chrome/NativeMessagingHosts",
+        Browser::Chromium => ".config/chromium/NativeMessagingHosts",
+        Browser::Brave => ".config/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+        Browser::Edge => ".config/microsoft-edge/NativeMessagingHosts",
+        Browser::Firefox => ".mozilla/native-messaging-hosts",
+    };
+    Ok(home.join(dir).join(format!("{}.json", HOST_NAME)))
+}
+
+#[cfg(target_os = "macos")]
+fn manifest_path(browser: Browser, _data_dir: &Path) -> Result<PathBuf, String> {
+    let home = directories::BaseDirs::new()
+        .ok_or("Home directory not found")?
+        .home_dir()
+        .to_path_buf();
+    let dir = match browser {
+        Browser::Chrome => "Google/Chrome",
+        Browser::Chromium => "Chromium",
+        Browser::Brave => "BraveSoftware/Brave-Browser",
+        Browser::Edge => "Microsoft Edge",
+        Browser::Firefox => "Mozilla",
+    };
+    Ok(home
+        .join("Library/Application Support")
+        .join(dir)
+        .join("NativeMessagingHosts")
+        .join(format!("{}.json", HOST_NAME)))
+}
+
+/**
+ * Windows browsers find the manifest through the registry, keep it in app data
+ */
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn manifest_path(browser: Browser, data_dir: &Path) -> Result<PathBuf, String> {
+    let suffix = if browser == Browser::Firefox {
+        "firefox"
+    } else {
+        "chromium"
+    };
+    Ok(data_dir.join(format!("{}.{}.json", HOST_NAME, suffix)))
+}
+
+#[cfg(windows)]
+fn registry_key(browser: Browser) -> String {
+    let vendor = match browser {
+        Browser::Chrome | Browser::Brave => "Google\\Chrome",
+        Browser::Chromium => "Chromium",
+        Browser::Edge => "Microsoft\\Edge",
+        Browser::Firefox => "Mozilla",
+    };
+    format!(
+        "HKCU\\Software\\{}\\NativeMessagingHosts\\{}",
+        vendor, HOST_NAME
+    )
+}
+
+#[cfg(windows)]
+fn register(browser: Browser, manifest: &Path) -> Result<(), String> {
+    let status = std::process::Command::new("reg")
+        .args([
+            "add",
+            &registry_key(browser),
+            "/ve",
+            "/t",
+            "REG_SZ",
+            "/f",
+            "/d",
+        ])
+        .arg(manifest)
+        .status()
+        .map_err(|e| format!("Failed to run reg: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("reg add exited with {}", status))
+    }
+}
+
+#[cfg(windows)]
+fn unregister(browser: Browser) -> Result<(), String> {
+    // Missing keys are fine, the host is simply not installed
+    let _ = std::process::Command::new("reg")
+        .args(["delete", &registry_key(browser), "/f"])
+        .status();
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn register(_browser: Browser, _manifest: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn unregister(_browser: Browser) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,681 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::DatabaseService;
+use crate::models::ClipboardItemModel;
+
+/**
+ * Columns a tab expands to for tabs_to_spaces
+ */
+pub const TAB_WIDTH_SETTING: &str = "paste_tab_width";
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+/**
+ * Conversion applied to an item's text when pasting it
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteFormat {
+    HtmlToMarkdown,
+    MarkdownToHtml,
+    PlainText, // drop HTML/RTF flavors, markup-only items are reduced to their text
+    TabsToSpaces,
+    CrlfToLf,
+    LfToCrlf,
+}
+
+/**
+ * Converted clipboard contents; `html` is written alongside the text when set
+ */
+pub struct Converted {
+    pub text: String,
+    pub html: Option<String>,
+}
+
+/**
+ * Convert a text item; `text` is its full content (see large_content::full_content)
+ */
+pub fn convert(
+    db: &DatabaseService,
+    item: &ClipboardItemModel,
+    text: String,
+    format: PasteFormat,
+) -> Result<Converted, String> {
+    if item.image_base64.is_some() || item.item_type == "file" {
+        return Err("Only text items can be converted".to_string());
+    }
+
+    // The HTML watcher stores markup as content when no html flavor is set
+    let markup = item
+        .html
+        .clone()
+        .or_else(|| (item.item_type == "html").then(|| text.clone()));
+
+    let converted = match format {
+        PasteFormat::HtmlToMarkdown => {
+            let html = markup.ok_or("Item has no HTML to convert")?;
+            plain(html_to_markdown(&html))
+        }
+        PasteFormat::MarkdownToHtml => Converted {
+            html: Some(markdown_to_html(&text)),
+            text,
+        },
+        PasteFormat::PlainText => match (markup, item.html.is_none()) {
+            // Markup-only item: the content is the HTML itself
+            (Some(html), true) => plain(html_to_text(&html)),
+            _ => plain(text),
+        },
+        PasteFormat::TabsToSpaces => {
+            let width = db
+                .get_setting(TAB_WIDTH_SETTING)
+                .ok()
+                .flatten()
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|width| *width > 0)
+                .unwrap_or(DEFAULT_TAB_WIDTH);
+            plain(tabs_to_spaces(&text, width))
+        }
+        PasteFormat::CrlfToLf => plain(to_lf(&text)),
+        PasteFormat::LfToCrlf => plain(to_lf(&text).replace('\n', "\r\n")),
+    };
+    Ok(converted)
+}
+
+fn plain(text: String) -> Converted {
+    Converted { text, html: None }
+}
+
+fn to_lf(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/**
+ * Expand tabs to the next multiple of `width` columns
+ */
+pub fn tabs_to_spaces(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let spaces = width - column % width;
+                out.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            '\n' | '\r' => {
+                out.push(c);
+                column = 0;
+            }
+            _ => {
+                out.push(c);
+                column += 1;
+            }
+        }
+    }
+    out
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Tag {
+        name: String,
+        closing: bool,
+        attrs: &'a str,
+    },
+}
+
+/**
+ * Split HTML into text and tags; comments, doctype and script/style bodies are dropped
+ */
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    let mut skip_until: Option<String> = None;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            if skip_until.is_none() {
+                tokens.push(Token::Text(rest));
+            }
+            break;
+        };
+        if open > 0 && skip_until.is_none() {
+            tokens.push(Token::Text(&rest[..open]));
+        }
+        rest = &rest[open..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(close) = rest.find('>') else {
+            if skip_until.is_none() {
+                tokens.push(Token::Text(rest));
+            }
+            break;
+        };
+
+        let inner = &rest[1..close];
+        rest = &rest[close + 1..];
+        let (closing, inner) = match inner.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+        let name_end = inner
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+
+        if let Some(until) = &skip_until {
+            if closing && &name == until {
+                skip_until = None;
+            }
+            continue;
+        }
+        if !closing && matches!(name.as_str(), "script" | "style" | "head" | "title") {
+            skip_until = Some(name);
+            continue;
+        }
+        if name.is_empty() || name.starts_with('!') || name.starts_with('?') {
+            continue;
+        }
+        tokens.push(Token::Tag {
+            name,
+            closing,
+            attrs: &inner[name_end..],
+        });
+    }
+    tokens
+}
+
+/**
+ * Value of a tag attribute, entities decoded
+ */
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(found) = lower[search..].find(name) {
+        let start = search + found;
+        search = start + name.len();
+        let before_ok = start == 0 || lower[..start].ends_with(|c: char| c.is_whitespace());
+        let after = attrs[search..].trim_start();
+        if !before_ok || !after.starts_with('=') {
+            continue;
+        }
+        let value = after[1..].trim_start();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+            _ => value
+                .split(|c: char| c.is_whitespace() || c == '>')
+                .next()
+                .unwrap_or(""),
+        };
+        return Some(decode_entities(value));
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| {
+                let entity = &rest[1..=end];
+                let c = match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                        .map(|hex| u32::from_str_radix(hex, 16).ok())
+                        .unwrap_or_else(|| entity.strip_prefix('#').and_then(|n| n.parse().ok()))
+                        .and_then(char::from_u32),
+                };
+                c.map(|c| (c, end + 2))
+            });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/**
+ * Markdown writer tracking line starts so text and blocks join cleanly
+ */
+struct MarkdownWriter {
+    out: String,
+    lists: Vec<Option<usize>>, // Some(next number) for ordered lists
+    links: Vec<Option<String>>,
+    quotes: Vec<usize>, // output offsets where open blockquotes start
+    pre: usize,
+}
+
+impl MarkdownWriter {
+    fn newline(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if self.pre > 0 {
+            self.out.push_str(&text);
+            return;
+        }
+
+        let mut collapsed = String::with_capacity(text.len());
+        for word in text.split_whitespace() {
+            if !collapsed.is_empty() {
+                collapsed.push(' ');
+            }
+            collapsed.push_str(word);
+        }
+        if text.starts_with(char::is_whitespace) && !self.at_line_start() {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        if self.at_line_start() || self.out.ends_with(' ') {
+            collapsed = collapsed.trim_start().to_string();
+        }
+        self.out.push_str(&collapsed);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn tag(&mut self, name: &str, closing: bool, attrs: &str) {
+        match (name, closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                self.blank_line();
+                let level = name[1..].parse().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "table", _) => self.blank_line(),
+            ("div" | "tr" | "section" | "article" | "header" | "footer", _) => self.newline(),
+            ("br", _) => self.out.push('\n'),
+            ("hr", false) => {
+                self.blank_line();
+                self.out.push_str("---\n\n");
+            }
+            ("strong" | "b", _) => self.out.push_str("**"),
+            ("em" | "i", _) => self.out.push('*'),
+            ("code", _) if self.pre == 0 => self.out.push('`'),
+            ("pre", false) => {
+                self.blank_line();
+                self.out.push_str("```\n");
+                self.pre += 1;
+            }
+            ("pre", true) => {
+                self.pre = self.pre.saturating_sub(1);
+                self.newline();
+                self.out.push_str("```\n\n");
+            }
+            ("a", false) => {
+                let href = attr(attrs, "href");
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.out.push_str(&format!("]({})", href));
+                }
+            }
+            ("img", false) => {
+                if let Some(src) = attr(attrs, "src") {
+                    let alt = attr(attrs, "alt").unwrap_or_default();
+                    self.out.push_str(&format!("![{}]({})", alt, src));
+                }
+            }
+            ("ul" | "ol", false) => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                } else {
+                    self.newline();
+                }
+                self.lists.push((name == "ol").then_some(1));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            ("li", false) => {
+                self.newline();
+                let depth = self.lists.len().max(1);
+                self.out.push_str(&"  ".repeat(depth - 1));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        self.out.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => self.out.push_str("- "),
+                }
+            }
+            ("li", true) => self.newline(),
+            ("td" | "th", true) => self.out.push(' '),
+            ("blockquote", false) => {
+                self.blank_line();
+                self.quotes.push(self.out.len());
+            }
+            ("blockquote", true) => {
+                if let Some(start) = self.quotes.pop() {
+                    let quoted: String = self.out[start..]
+                        .trim_end()
+                        .lines()
+                        .map(|line| format!("> {}\n", line).replace("> \n", ">\n"))
+                        .collect();
+                    self.out.truncate(start);
+                    self.out.push_str(&quoted);
+                    self.blank_line();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/**
+ * Convert HTML to Markdown: headings, emphasis, code, links, images, lists, quotes and rules
+ * Unknown tags are dropped and their text kept
+ */
+pub fn html_to_markdown(html: &str) -> String {
+    let mut writer = MarkdownWriter {
+        out: String::new(),
+        lists: Vec::new(),
+        links: Vec::new(),
+        quotes: Vec::new(),
+        pre: 0,
+    };
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => writer.text(text),
+            Token::Tag {
+                name,
+                closing,
+                attrs,
+            } => writer.tag(&name, closing, attrs),
+        }
+    }
+
+    tidy(&writer.out)
+}
+
+/**
+ * Visible text of HTML with block elements on their own lines
+ */
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => out.push_str(&decode_entities(text)),
+            Token::Tag { name, .. } => {
+                if matches!(
+                    name.as_str(),
+                    "p" | "div" | "br" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                ) && !out.ends_with('\n')
+                {
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    tidy(&out)
+}
+
+/**
+ * Trim trailing spaces and squeeze runs of blank lines
+ */
+fn tidy(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank += 1;
+            if blank > 1 {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+/**
+ * Convert common Markdown to HTML: headings, paragraphs, flat lists, fenced code,
+ * blockquotes, rules, and inline emphasis, code, links and images
+ */
+pub fn markdown_to_html(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    let flush = |paragraph: &mut Vec<&str>, out: &mut String| {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", inline(&paragraph.join("\n"))));
+            paragraph.clear();
+        }
+    };
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut out);
+            let language = fence.trim();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                code.push(lines[i]);
+                i += 1;
+            }
+            let class = if language.is_empty() {
+                String::new()
+            } else {
+                format!(" class=\"language-{}\"", escape(language))
+            };
+            out.push_str(&format!(
+                "<pre><code{}>{}</code></pre>\n",
+                class,
+                escape(&code.join("\n"))
+            ));
+            i += 1;
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut out);
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut paragraph, &mut out);
+            out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline(text)));
+        } else if is_rule(trimmed) {
+            flush(&mut paragraph, &mut out);
+            out.push_str("<hr>\n");
+        } else if trimmed.starts_with('>') {
+            flush(&mut paragraph, &mut out);
+            let mut quoted = Vec::new();
+            while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                let line = lines[i].trim_start()[1..].strip_prefix(' ');
+                quoted.push(line.unwrap_or(&lines[i].trim_start()[1..]));
+                i += 1;
+            }
+            out.push_str(&format!(
+                "<blockquote>\n{}</blockquote>\n",
+                markdown_to_html(&quoted.join("\n"))
+            ));
+            continue;
+        } else if let Some((ordered, _)) = list_item(trimmed) {
+            flush(&mut paragraph, &mut out);
+            let tag = if ordered { "ol" } else { "ul" };
+            out.push_str(&format!("<{}>\n", tag));
+            while i < lines.len() {
+                match list_item(lines[i].trim()) {
+                    Some((item_ordered, text)) if item_ordered == ordered => {
+                        out.push_str(&format!("<li>{}</li>\n", inline(text)));
+                        i += 1;
+                    }
+                    _ => break,
+                }
+            }
+            out.push_str(&format!("</{}>\n", tag));
+            continue;
+        } else {
+            paragraph.push(trimmed);
+        }
+        i += 1;
+    }
+    flush(&mut paragraph, &mut out);
+    out
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level, text.trim_end_matches('#').trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+/**
+ * (ordered, text) of a list item line
+ */
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return Some((false, text));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let text = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    (digits > 0).then_some((true, text))
+}
+
+/**
+ * Inline Markdown: `code`, **strong**, *em*, _em_, [links](url) and ![images](url)
+ */
+fn inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+
+    'chars: while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                out.push_str(&format!("<code>{}</code>", escape(&rest[1..=end])));
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+        if c == '!' || c == '[' {
+            if let Some((label, url, len)) = link(rest.strip_prefix('!').unwrap_or(rest)) {
+                if c == '!' {
+                    out.push_str(&format!(
+                        "<img src=\"{}\" alt=\"{}\">",
+                        escape(url),
+                        escape(label)
+                    ));
+                    rest = &rest[len + 1..];
+                } else {
+                    out.push_str(&format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape(url),
+                        inline(label)
+                    ));
+                    rest = &rest[len..];
+                }
+                continue;
+            }
+        }
+        for (marker, tag) in [("**", "strong"), ("__", "strong"), ("*", "em"), ("_", "em")] {
+            let word_start = out.is_empty() || !out.ends_with(|c: char| c.is_alphanumeric());
+            if rest.starts_with(marker) && (marker.starts_with('*') || word_start) {
+                let body = &rest[marker.len()..];
+                if let Some(end) = body.find(marker).filter(|end| *end > 0) {
+                    out.push_str(&format!("<{0}>{1}</{0}>", tag, inline(&body[..end])));
+                    rest = &body[end + marker.len()..];
+                    continue 'chars;
+                }
+            }
+        }
+
+        match c {
+            '\n' => out.push_str("<br>\n"),
+            _ => out.push_str(&escape(&c.to_string())),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/**
+ * Parse `[label](url)` at the start of `text`, returns (label, url, length)
+ */
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let text_start = text.strip_prefix('[')?;
+    let label_end = text_start.find("](")?;
+    let url_start = &text_start[label_end + 2..];
+    let url_end = url_start.find(')')?;
+    Some((
+        &text_start[..label_end],
+        &url_start[..url_end],
+        1 + label_end + 2 + url_end + 1,
+    ))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
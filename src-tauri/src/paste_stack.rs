@@ -0,0 +1,178 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/**
+ * Order in which queued items are popped from the paste stack
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasteStackOrder {
+    Fifo,
+    Lifo,
+}
+
+/**
+ * Snapshot of the paste stack returned to the frontend
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteStackState {
+    pub active: bool,
+    pub order: PasteStackOrder,
+    pub remaining: u32,
+    pub item_ids: Vec<String>,
+}
+
+/**
+ * Paste stack (queue) mode
+ * While active, the next N saved copies are queued and can then be
+ * popped one by one for sequential pasting
+ */
+pub struct PasteStack {
+    state: Mutex<PasteStackState>,
+}
+
+impl PasteStack {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(PasteStackState {
+                active: false,
+                order: PasteStackOrder::Fifo,
+                remaining: 0,
+                item_ids: Vec::new(),
+            }),
+        }
+    }
+
+    /**
+     * Enter stack mode, queueing the next `capacity` copies
+     */
+    pub fn start(&self, capacity: u32, order: PasteStackOrder) -> PasteStackState {
+        let mut state = self.state.lock();
+        state.active = capacity > 0;
+        state.order = order;
+        state.remaining = capacity;
+        state.item_ids.clear();
+        state.clone()
+    }
+
+    /**
+     * Leave stack mode, keeping already queued items
+     */
+    pub fn stop(&self) -> PasteStackState {
+        let mut state = self.state.lock();
+        state.active = false;
+        state.remaining = 0;
+        state.clone()
+    }
+
+    /**
+     * Queue a newly saved item if stack mode is collecting
+     * The same id can be queued more than once, every copy is a paste
+     */
+    pub fn capture(&self, item_id: &str) -> bool {
+        let mut state = self.state.lock();
+        if !state.active || state.remaining == 0 {
+            return false;
+        }
+
+        state.item_ids.push(item_id.to_string());
+        state.remaining -= 1;
+        if state.remaining == 0 {
+            state.active = false;
+        }
+        true
+    }
+
+    /**
+     * Take the next item id according to the configured order
+     */
+    pub fn pop(&self) -> Option<String> {
+        let mut state = self.state.lock();
+        if state.item_ids.is_empty() {
+            return None;
+        }
+
+        match state.order {
+            PasteStackOrder::Fifo => Some(state.item_ids.remove(0)),
+            PasteStackOrder::Lifo => state.item_ids.pop(),
+        }
+    }
+
+    /**
+     * Replace the queue order; unknown ids are ignored and missing ones kept at the end
+     */
+    pub fn reorder(&self, item_ids: Vec<String>) -> PasteStackState {
+        let mut state = self.state.lock();
+        let mut remaining: VecDeque<String> = state.item_ids.drain(..).collect();
+        let mut reordered = Vec::with_capacity(remaining.len());
+
+        for id in item_ids {
+            if let Some(pos) = remaining.iter().position(|queued| *queued == id) {
+                if let Some(queued) = remaining.remove(pos) {
+                    reordered.push(queued);
+                }
+            }
+        }
+        reordered.extend(remaining);
+
+        state.item_ids = reordered;
+        state.clone()
+    }
+
+    /**
+     * Drop all queued items and leave stack mode
+     */
+    pub fn clear(&self) -> PasteStackState {
+        let mut state = self.state.lock();
+        state.active = false;
+        state.remaining = 0;
+        state.item_ids.clear();
+        state.clone()
+    }
+
+    /**
+     * Current state snapshot
+     */
+    pub fn snapshot(&self) -> PasteStackState {
+        self.state.lock().clone()
+    }
+}
+
+impl Default for PasteStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_copy_is_queued_twice() {
+        let stack = PasteStack::new();
+        stack.start(3, PasteStackOrder::Fifo);
+
+        assert!(stack.capture("a"));
+        assert!(stack.capture("b"));
+        assert!(stack.capture("a"));
+        assert!(!stack.capture("c"));
+
+        assert_eq!(stack.pop().as_deref(), Some("a"));
+        assert_eq!(stack.pop().as_deref(), Some("b"));
+        assert_eq!(stack.pop().as_deref(), Some("a"));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn lifo_pops_latest_first() {
+        let stack = PasteStack::new();
+        stack.start(2, PasteStackOrder::Lifo);
+        stack.capture("a");
+        stack.capture("b");
+
+        assert_eq!(stack.pop().as_deref(), Some("b"));
+        assert_eq!(stack.pop().as_deref(), Some("a"));
+    }
+}
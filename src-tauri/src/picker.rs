@@ -0,0 +1,254 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::db::DatabaseService;
+use crate::models::{ClipboardQueryFilter, PickerPageModel};
+
+/**
+ * Keystrokes closer together than this only run the last filter
+ */
+const DEBOUNCE: Duration = Duration::from_millis(120);
+
+/**
+ * Newest items considered per query, and how much of each item is matched
+ */
+const CANDIDATE_LIMIT: u64 = 5000;
+const MATCH_CHARS: usize = 1000;
+
+/**
+ * Sessions unused for this long are dropped when a new one starts
+ */
+const SESSION_IDLE: Duration = Duration::from_secs(10 * 60);
+
+struct PickerSession {
+    filter: ClipboardQueryFilter,
+    page_size: usize,
+    query: String,
+    generation: u64, // bumped on every keystroke to drop superseded filters
+    matches: Vec<String>,
+    page: usize,
+    last_used: Instant,
+}
+
+/**
+ * Server-side state for the history picker
+ * Each session keeps its ranked matches warm so paging never re-runs the search
+ */
+pub struct PickerSessions {
+    sessions: Mutex<HashMap<String, PickerSession>>,
+}
+
+impl PickerSessions {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /**
+     * Start a session over the items matching `filter` (its search is ignored)
+     */
+    pub fn start(
+        &self,
+        db: &DatabaseService,
+        filter: ClipboardQueryFilter,
+        page_size: usize,
+    ) -> Result<PickerPageModel, String> {
+        let matches = rank(db, &filter, "")?;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        {
+            let mut sessions = self.sessions.lock();
+            sessions.retain(|_, session| session.last_used.elapsed() < SESSION_IDLE);
+            sessions.insert(
+                id.clone(),
+                PickerSession {
+                    filter,
+                    page_size: page_size.max(1),
+                    query: String::new(),
+                    generation: 0,
+                    matches,
+                    page: 0,
+                    last_used: Instant::now(),
+                },
+            );
+        }
+
+        self.current_page(db, &id)
+    }
+
+    /**
+     * Re-rank the session for `text` after the debounce delay
+     * Returns None when a newer filter for the same session superseded this one
+     */
+    pub async fn filter(
+        &self,
+        db: &DatabaseService,
+        id: &str,
+        text: String,
+    ) -> Result<Option<PickerPageModel>, String> {
+        let generation = self.with_session(id, |session| {
+            session.generation += 1;
+            session.generation
+        })?;
+
+        tokio::time::sleep(DEBOUNCE).await;
+        let Some(filter) = self.with_session(id, |session| {
+            (session.generation == generation).then(|| session.filter.clone())
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let matches = rank(db, &filter, &text)?;
+        let current = self.with_session(id, |session| {
+            if session.generation != generation {
+                return false;
+            }
+            session.query = text;
+            session.matches = matches;
+            session.page = 0;
+            true
+        })?;
+
+        if !current {
+            return Ok(None);
+        }
+        self.current_page(db, id).map(Some)
+    }
+
+    /**
+     * Move to the next (1) or previous (-1) page, staying within bounds
+     */
+    pub fn turn_page(
+        &self,
+        db: &DatabaseService,
+        id: &str,
+        delta: isize,
+    ) -> Result<PickerPageModel, String> {
+        self.with_session(id, |session| {
+            let last = page_count(session).saturating_sub(1);
+            session.page = session.page.saturating_add_signed(delta).min(last);
+        })?;
+        self.current_page(db, id)
+    }
+
+    pub fn close(&self, id: &str) -> bool {
+        self.sessions.lock().remove(id).is_some()
+    }
+
+    fn current_page(&self, db: &DatabaseService, id: &str) -> Result<PickerPageModel, String> {
+        let (query, page, total_matches, has_next, ids) = self.with_session(id, |session| {
+            let start = session.page * session.page_size;
+            let ids: Vec<String> = session
+                .matches
+                .iter()
+                .skip(start)
+                .take(session.page_size)
+                .cloned()
+                .collect();
+            (
+                session.query.clone(),
+                session.page,
+                session.matches.len(),
+                start + session.page_size < session.matches.len(),
+                ids,
+            )
+        })?;
+
+        Ok(PickerPageModel {
+            session_id: id.to_string(),
+            query,
+            page,
+            total_matches,
+            has_prev: page > 0,
+            has_next,
+            items: db.get_item_summaries(&ids).map_err(|e| e.to_string())?,
+        })
+    }
+
+    fn with_session<T>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut PickerSession) -> T,
+    ) -> Result<T, String> {
+        let mut sessions = self.sessions.lock();
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| "Picker session not found".to_string())?;
+        session.last_used = Instant::now();
+        Ok(f(session))
+    }
+}
+
+impl Default for PickerSessions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn page_count(session: &PickerSession) -> usize {
+    session.matches.len().div_ceil(session.page_size).max(1)
+}
+
+/**
+ * Ids of the candidates matching `query`, best first; an empty query keeps history order
+ */
+fn rank(
+    db: &DatabaseService,
+    filter: &ClipboardQueryFilter,
+    query: &str,
+) -> Result<Vec<String>, String> {
+    let candidates = db
+        .get_match_candidates(filter, MATCH_CHARS, CANDIDATE_LIMIT)
+        .map_err(|e| e.to_string())?;
+
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Ok(candidates.into_iter().map(|(id, _)| id).collect());
+    }
+
+    let mut scored: Vec<(i64, String)> = candidates
+        .into_iter()
+        .filter_map(|(id, text)| fuzzy_score(&text, &query).map(|score| (score, id)))
+        .collect();
+    // Stable sort keeps history order between equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    Ok(scored.into_iter().map(|(_, id)| id).collect())
+}
+
+/**
+ * Score `text` against a lowercase query, None when the query isn't a subsequence
+ * Exact substrings rank first, then consecutive runs and matches at word starts
+ */
+pub fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let lower = text.to_lowercase();
+    if let Some(position) = lower.find(query) {
+        let position = lower[..position].chars().count() as i64;
+        return Some(10_000 - position.min(1_000));
+    }
+
+    let mut needle = query.chars().peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut run = 0;
+    for c in lower.chars() {
+        let Some(&wanted) = needle.peek() else {
+            break;
+        };
+        if c == wanted {
+            needle.next();
+            run += 1;
+            score += run * 2;
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 5;
+            }
+        } else {
+            run = 0;
+        }
+        previous = Some(c);
+    }
+
+    needle.peek().is_none().then_some(score)
+}
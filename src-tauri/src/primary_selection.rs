@@ -0,0 +1,153 @@
+#[cfg(target_os = "linux")]
+use std::process::{Command, Stdio};
+
+use tauri::AppHandle;
+
+#[cfg(target_os = "linux")]
+use crate::capabilities::{self, Feature};
+#[cfg(target_os = "linux")]
+use crate::capture::{self, CaptureSource, CapturedItem};
+#[cfg(target_os = "linux")]
+use crate::db::DatabaseService;
+#[cfg(target_os = "linux")]
+use crate::models::SOURCE_PRIMARY_SELECTION;
+#[cfg(target_os = "linux")]
+use crate::shutdown::Shutdown;
+#[cfg(target_os = "linux")]
+use tauri::Manager;
+
+pub const ENABLED_SETTING: &str = "primary_selection_enabled";
+pub const MODE_SETTING: &str = "primary_selection_mode";
+
+/**
+ * Polls the selection must stay unchanged before it is saved, so a drag in progress isn't
+ */
+#[cfg(target_os = "linux")]
+const STABLE_POLLS: u32 = 2;
+
+/**
+ * Start the PRIMARY selection watcher (Linux only)
+ * Polls the selection and saves new text through the same pipeline as clipboard copies.
+ * Outside "merge" mode items are stored as SOURCE_PRIMARY_SELECTION, kept apart from copies.
+ */
+#[cfg(target_os = "linux")]
+pub fn start_watcher(app: AppHandle) {
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("primary-selection", move |token| {
+        let mut last = String::new();
+        let mut pending = String::new();
+        let mut stable_polls = 0;
+
+        while token.sleep(std::time::Duration::from_millis(500)) {
+            let Some(db) = app.try_state::<DatabaseService>() else {
+                continue;
+            };
+
+            let enabled = matches!(
+                db.get_setting(ENABLED_SETTING).ok().flatten().as_deref(),
+                Some("true")
+            );
+            if !enabled {
+                continue;
+            }
+
+            let Ok(text) = read_primary() else {
+                continue;
+            };
+            if text.trim().is_empty() || text == last {
+                continue;
+            }
+            if text != pending {
+                pending = text;
+                stable_polls = 0;
+                continue;
+            }
+            stable_polls += 1;
+            if stable_polls < STABLE_POLLS {
+                continue;
+            }
+
+            // Growing or shrinking the previous selection refines it, it isn't a new one
+            let refined = !last.is_empty() && (text.starts_with(&last) || last.starts_with(&text));
+            last = std::mem::take(&mut pending);
+            if refined {
+                continue;
+            }
+
+            let mut captured = CapturedItem::text(last.clone());
+            if db.get_setting(MODE_SETTING).ok().flatten().as_deref() != Some("merge") {
+                captured.source = SOURCE_PRIMARY_SELECTION;
+            }
+
+            if let Err(e) = capture::save(&app, &db, captured, CaptureSource::PrimarySelection) {
+                log::error!("Failed to save primary selection: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn start_watcher(_app: AppHandle) {}
+
+/**
+ * Read the current PRIMARY selection text
+ */
+#[cfg(target_os = "linux")]
+fn read_primary() -> Result<String, String> {
+    let output = if is_wayland() {
+        Command::new("wl-paste")
+            .args(["--primary", "--no-newline"])
+            .output()
+    } else {
+        Command::new("xclip")
+            .args(["-o", "-selection", "primary"])
+            .output()
+    }
+    .map_err(|e| format!("Failed to read primary selection: {}", e))?;
+
+    if !output.status.success() {
+        return Err("Primary selection is empty".to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/**
+ * Write text to the PRIMARY selection so middle-click pastes it
+ */
+#[cfg(target_os = "linux")]
+pub fn write_primary(text: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    capabilities::require(Feature::PrimarySelection)?;
+    let (program, args): (&str, &[&str]) = if is_wayland() {
+        ("wl-copy", &["--primary"])
+    } else {
+        ("xclip", &["-i", "-selection", "primary"])
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to write primary selection: {}", e))?;
+    }
+    child
+        .wait()
+        .map_err(|e| format!("Failed to write primary selection: {}", e))?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn write_primary(_text: &str) -> Result<(), String> {
+    Err("The primary selection is only available on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
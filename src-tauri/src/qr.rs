@@ -0,0 +1,33 @@
+use image::Luma;
+use qrcode::QrCode;
+
+/**
+ * Longest text encoded into a QR code; larger codes are hard for phones to scan
+ */
+pub const MAX_QR_BYTES: usize = 1200;
+
+/**
+ * Render text as a QR code PNG
+ */
+pub fn render_png(text: &str) -> Result<Vec<u8>, String> {
+    if text.is_empty() {
+        return Err("Item has no text to encode".to_string());
+    }
+    if text.len() > MAX_QR_BYTES {
+        return Err(format!(
+            "Item text is too long for a QR code ({} bytes, max {})",
+            text.len(),
+            MAX_QR_BYTES
+        ));
+    }
+
+    let code =
+        QrCode::new(text.as_bytes()).map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    let image = code.render::<Luma<u8>>().min_dimensions(256, 256).build();
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode QR code image: {}", e))?;
+    Ok(png)
+}
@@ -0,0 +1,319 @@
+use rusqlite::types::Value;
+
+use crate::models::ClipboardQueryFilter;
+
+/**
+ * Small SELECT builder for clipboard_items
+ * Every user-supplied value is bound as a parameter, never interpolated
+ */
+pub struct ItemQuery {
+    columns: String,
+    column_values: Vec<Value>,
+    table: String,
+    conditions: Vec<String>,
+    values: Vec<Value>,
+    order_by: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+impl ItemQuery {
+    pub fn select(columns: &str) -> Self {
+        Self {
+            columns: columns.to_string(),
+            column_values: Vec::new(),
+            table: "clipboard_items".to_string(),
+            conditions: Vec::new(),
+            values: Vec::new(),
+            order_by: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /**
+     * Bind values for ? placeholders in the selected columns
+     */
+    pub fn column_values(mut self, values: impl IntoIterator<Item = Value>) -> Self {
+        self.column_values.extend(values);
+        self
+    }
+
+    /**
+     * Read from another table or view with the clipboard_items columns
+     */
+    pub fn from(mut self, table: &str) -> Self {
+        self.table = table.to_string();
+        self
+    }
+
+    /**
+     * Apply the standard history filters (search, type, language, pinned, date range)
+     */
+    pub fn filter(mut self, filter: &ClipboardQueryFilter) -> Self {
+        if let Some(search) = &filter.search {
+            self = self.and_where(
+                "content LIKE ? ESCAPE '\\'",
+                [Value::Text(format!("%{}%", escape_like(search)))],
+            );
+        }
+
+        if let Some(item_type) = &filter.item_type {
+            self = self.and_where("item_type = ?", [Value::Text(item_type.clone())]);
+        }
+
+        if let Some(language) = &filter.language {
+            self = self.and_where("language = ?", [Value::Text(language.clone())]);
+        }
+
+        if let Some(is_pinned) = filter.is_pinned {
+            self = self.and_where("is_pinned = ?", [Value::Integer(is_pinned as i64)]);
+        }
+
+        if let Some(since) = filter.since {
+            self = self.and_where("timestamp >= ?", [Value::Integer(since)]);
+        }
+
+        if let Some(until) = filter.until {
+            self = self.and_where("timestamp < ?", [Value::Integer(until)]);
+        }
+
+        self
+    }
+
+    /**
+     * Add a condition; `clause` must be a static SQL fragment using ? placeholders
+     */
+    pub fn and_where(mut self, clause: &str, values: impl IntoIterator<Item = Value>) -> Self {
+        self.conditions.push(format!("({})", clause));
+        self.values.extend(values);
+        self
+    }
+
+    /**
+     * Set the ORDER BY clause; must be a static SQL fragment
+     */
+    pub fn order_by(mut self, order_by: &str) -> Self {
+        self.order_by = Some(order_by.to_string());
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /**
+     * Produce the SQL string and its bound values
+     */
+    pub fn build(self) -> (String, Vec<Value>) {
+        let mut sql = format!("SELECT {} FROM {}", self.columns, self.table);
+        let mut values = self.column_values;
+        values.extend(self.values);
+
+        if !self.conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&self.conditions.join(" AND "));
+        }
+
+        if let Some(order_by) = &self.order_by {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(order_by);
+        }
+
+        // SQLite needs a LIMIT before OFFSET; -1 means no limit
+        if self.limit.is_some() || self.offset.is_some() {
+            sql.push_str(" LIMIT ?");
+            values.push(Value::Integer(self.limit.map(clamp_i64).unwrap_or(-1)));
+        }
+
+        if let Some(offset) = self.offset {
+            sql.push_str(" OFFSET ?");
+            values.push(Value::Integer(clamp_i64(offset)));
+        }
+
+        (sql, values)
+    }
+}
+
+/**
+ * Escape LIKE wildcards so search text matches literally
+ */
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn clamp_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{params_from_iter, Connection};
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    /**
+     * Run a built query against a scratch table and return the matching contents
+     */
+    fn run(conn: &Connection, query: ItemQuery) -> Vec<String> {
+        let (sql, values) = query.build();
+        let mut stmt = conn.prepare(&sql).unwrap();
+        stmt.query_map(params_from_iter(values), |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn scratch() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clipboard_items (content TEXT, item_type TEXT, timestamp INTEGER);
+             INSERT INTO clipboard_items VALUES ('first', 'text', 1), ('100%_done', 'text', 2),
+                 ('second', 'html', 3);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn select_without_clauses() {
+        let (sql, values) = ItemQuery::select("id, content").build();
+        assert_eq!(sql, "SELECT id, content FROM clipboard_items");
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn filter_binds_every_value() {
+        let filter = ClipboardQueryFilter {
+            search: Some("needle".to_string()),
+            item_type: Some("text".to_string()),
+            is_pinned: Some(true),
+            since: Some(10),
+            until: Some(20),
+            ..Default::default()
+        };
+        let (sql, values) = ItemQuery::select("id")
+            .filter(&filter)
+            .order_by("timestamp DESC")
+            .limit(5)
+            .offset(10)
+            .build();
+
+        assert_eq!(
+            sql,
+            "SELECT id FROM clipboard_items WHERE (content LIKE ? ESCAPE '\\') AND (item_type = ?) \
+             AND (is_pinned = ?) AND (timestamp >= ?) AND (timestamp < ?) \
+             ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+        );
+        assert_eq!(
+            values,
+            vec![
+                text("%needle%"),
+                text("text"),
+                Value::Integer(1),
+                Value::Integer(10),
+                Value::Integer(20),
+                Value::Integer(5),
+                Value::Integer(10),
+            ]
+        );
+    }
+
+    #[test]
+    fn column_values_come_before_conditions() {
+        let (sql, values) = ItemQuery::select("id, content = ? AS exact")
+            .column_values([text("a")])
+            .and_where("item_type = ?", [text("b")])
+            .build();
+        assert_eq!(
+            sql,
+            "SELECT id, content = ? AS exact FROM clipboard_items WHERE (item_type = ?)"
+        );
+        assert_eq!(values, vec![text("a"), text("b")]);
+    }
+
+    #[test]
+    fn injection_attempts_stay_bound_values() {
+        let attack = "x' OR 1=1; DROP TABLE clipboard_items; --";
+        let filter = ClipboardQueryFilter {
+            search: Some(attack.to_string()),
+            item_type: Some(attack.to_string()),
+            ..Default::default()
+        };
+        let (sql, values) = ItemQuery::select("content").filter(&filter).build();
+        assert!(!sql.contains("DROP"));
+        assert!(!sql.contains("OR 1=1"));
+        assert_eq!(values[1], text(attack));
+
+        let conn = scratch();
+        let query = ItemQuery::select("content").filter(&filter);
+        assert!(run(&conn, query).is_empty());
+        // The table survived and still holds every row
+        assert_eq!(run(&conn, ItemQuery::select("content")).len(), 3);
+    }
+
+    #[test]
+    fn search_wildcards_match_literally() {
+        let filter = ClipboardQueryFilter {
+            search: Some("%_".to_string()),
+            ..Default::default()
+        };
+        let (_, values) = ItemQuery::select("content").filter(&filter).build();
+        assert_eq!(values, vec![text("%\\%\\_%")]);
+
+        let conn = scratch();
+        let query = ItemQuery::select("content").filter(&filter);
+        assert_eq!(run(&conn, query), vec!["100%_done".to_string()]);
+    }
+
+    #[test]
+    fn limit_zero_returns_nothing() {
+        let (sql, values) = ItemQuery::select("content").limit(0).build();
+        assert_eq!(sql, "SELECT content FROM clipboard_items LIMIT ?");
+        assert_eq!(values, vec![Value::Integer(0)]);
+        assert!(run(&scratch(), ItemQuery::select("content").limit(0)).is_empty());
+    }
+
+    #[test]
+    fn offset_without_limit_is_unbounded() {
+        let (sql, values) = ItemQuery::select("content").offset(1).build();
+        assert_eq!(sql, "SELECT content FROM clipboard_items LIMIT ? OFFSET ?");
+        assert_eq!(values, vec![Value::Integer(-1), Value::Integer(1)]);
+
+        let query = ItemQuery::select("content").order_by("timestamp").offset(1);
+        assert_eq!(run(&scratch(), query), vec!["100%_done", "second"]);
+    }
+
+    #[test]
+    fn huge_limit_and_offset_are_clamped() {
+        let (_, values) = ItemQuery::select("content")
+            .limit(u64::MAX)
+            .offset(u64::MAX)
+            .build();
+        assert_eq!(
+            values,
+            vec![Value::Integer(i64::MAX), Value::Integer(i64::MAX)]
+        );
+
+        let query = ItemQuery::select("content")
+            .limit(u64::MAX)
+            .offset(u64::MAX);
+        assert!(run(&scratch(), query).is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::files;
+use crate::models::ClipboardItemModel;
+
+/**
+ * Backend action that can be run on a history item
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAction {
+    OpenUrl,
+    RevealFile,
+    OpenImage,
+    ComposeMail,
+}
+
+impl QuickAction {
+    /**
+     * Same name as the serialized form
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            QuickAction::OpenUrl => "open_url",
+            QuickAction::RevealFile => "reveal_file",
+            QuickAction::OpenImage => "open_image",
+            QuickAction::ComposeMail => "compose_mail",
+        }
+    }
+
+    /**
+     * Display name for error feedback
+     */
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickAction::OpenUrl => "Open URL",
+            QuickAction::RevealFile => "Reveal file",
+            QuickAction::OpenImage => "Open image",
+            QuickAction::ComposeMail => "Compose mail",
+        }
+    }
+}
+
+/**
+ * Actions that apply to the item, the first one is the double-click default
+ */
+pub fn available(item: &ClipboardItemModel) -> Vec<QuickAction> {
+    let mut actions = Vec::new();
+
+    if item.image_base64.is_some() {
+        actions.push(QuickAction::OpenImage);
+    }
+    if first_existing_file(item).is_some() {
+        actions.push(QuickAction::RevealFile);
+    }
+    if as_url(&item.content).is_some() {
+        actions.push(QuickAction::OpenUrl);
+    }
+    if as_email(&item.content).is_some() {
+        actions.push(QuickAction::ComposeMail);
+    }
+    actions
+}
+
+/**
+ * Run an action on the item
+ */
+pub fn execute(
+    app: &AppHandle,
+    item: &ClipboardItemModel,
+    action: QuickAction,
+) -> Result<(), String> {
+    let opener = app.opener();
+
+    match action {
+        QuickAction::OpenUrl => {
+            let url = as_url(&item.content).ok_or("Item is not a URL")?;
+            opener
+                .open_url(url, None::<&str>)
+                .map_err(|e| e.to_string())
+        }
+        QuickAction::ComposeMail => {
+            let email = as_email(&item.content).ok_or("Item is not an email address")?;
+            opener
+                .open_url(format!("mailto:{}", email), None::<&str>)
+                .map_err(|e| e.to_string())
+        }
+        QuickAction::RevealFile => {
+            let path = first_existing_file(item).ok_or("Item has no existing file")?;
+            opener.reveal_item_in_dir(path).map_err(|e| e.to_string())
+        }
+        QuickAction::OpenImage => {
+            let path = write_temp_image(item)?;
+            opener
+                .open_path(path.display().to_string(), None::<&str>)
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/**
+ * The trimmed content if it is a single http(s) URL
+ */
+fn as_url(content: &str) -> Option<&str> {
+    let trimmed = content.trim();
+    let is_url = (trimmed.starts_with("http://") || trimmed.starts_with("https://"))
+        && !trimmed.contains(char::is_whitespace);
+    is_url.then_some(trimmed)
+}
+
+/**
+ * The trimmed content if it looks like a single email address
+ */
+fn as_email(content: &str) -> Option<&str> {
+    let trimmed = content.trim().trim_start_matches("mailto:");
+    let (local, domain) = trimmed.split_once('@')?;
+    let valid = !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !trimmed.contains(char::is_whitespace)
+        && trimmed.matches('@').count() == 1;
+    valid.then_some(trimmed)
+}
+
+fn first_existing_file(item: &ClipboardItemModel) -> Option<String> {
+    files::parse_paths(item.file_paths.as_deref()?)
+        .ok()?
+        .into_iter()
+        .find(|path| std::path::Path::new(path).exists())
+}
+
+/**
+ * Decode the item image into a temp file the default viewer can open
+ */
+fn write_temp_image(item: &ClipboardItemModel) -> Result<std::path::PathBuf, String> {
+    let image = item.image_base64.as_deref().ok_or("Item is not an image")?;
+    let data = image
+        .split_once("base64,")
+        .map(|(_, data)| data)
+        .unwrap_or(image);
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Invalid image data: {}", e))?;
+
+    let extension = image::guess_format(&bytes)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("png");
+    let path = std::env::temp_dir().join(format!("copyclip-{}.{}", item.id, extension));
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write image: {}", e))?;
+    Ok(path)
+}
@@ -0,0 +1,198 @@
+use parking_lot::Mutex;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use crate::capabilities::{self, Feature};
+
+/**
+ * How long a freshly started recorder gets to fail, e.g. on a missing display or permission
+ */
+const STARTUP_GRACE: std::time::Duration = std::time::Duration::from_millis(300);
+
+/**
+ * Running screen recording process
+ */
+struct Recording {
+    child: Child,
+    path: PathBuf,
+    uses_ffmpeg: bool,
+}
+
+/**
+ * Screen recorder driving the platform's recording tool
+ * Only one recording can run at a time
+ */
+pub struct ScreenRecorder {
+    active: Mutex<Option<Recording>>,
+}
+
+impl ScreenRecorder {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(None),
+        }
+    }
+
+    /**
+     * Start recording into `output_dir`, returns the output file path
+     */
+    pub fn start(&self, output_dir: PathBuf) -> Result<PathBuf, String> {
+        let mut active = self.active.lock();
+        if active.is_some() {
+            return Err("A screen recording is already running".to_string());
+        }
+        capabilities::require(Feature::ScreenRecording)?;
+
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create recordings folder: {}", e))?;
+
+        let file_name = format!(
+            "copyclip-recording-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let (mut cmd, path, uses_ffmpeg) = recording_command(&output_dir, &file_name)?;
+
+        let program = cmd.get_program().to_string_lossy().to_string();
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+
+        // Spawning succeeds even when the recorder gives up right away
+        std::thread::sleep(STARTUP_GRACE);
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let _ = std::fs::remove_file(&path);
+                return Err(format!(
+                    "{} exited right after starting ({})",
+                    program, status
+                ));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                let _ = child.kill();
+                return Err(format!("Failed to check {}: {}", program, e));
+            }
+        }
+
+        log::info!("Screen recording started: {}", path.display());
+        *active = Some(Recording {
+            child,
+            path: path.clone(),
+            uses_ffmpeg,
+        });
+        Ok(path)
+    }
+
+    /**
+     * Stop the running recording and wait for the file to be finalized
+     */
+    pub fn stop(&self) -> Result<PathBuf, String> {
+        let mut recording = self
+            .active
+            .lock()
+            .take()
+            .ok_or_else(|| "No screen recording is running".to_string())?;
+
+        if recording.uses_ffmpeg {
+            // ffmpeg finalizes the container when it reads 'q' on stdin
+            if let Some(stdin) = recording.child.stdin.as_mut() {
+                use std::io::Write;
+                let _ = stdin.write_all(b"q");
+            }
+        } else {
+            interrupt(&mut recording.child)?;
+        }
+
+        recording
+            .child
+            .wait()
+            .map_err(|e| format!("Failed to stop screen recording: {}", e))?;
+
+        if !recording.path.exists() {
+            return Err("Screen recording produced no output file".to_string());
+        }
+
+        log::info!("Screen recording saved: {}", recording.path.display());
+        Ok(recording.path)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active.lock().is_some()
+    }
+}
+
+impl Default for ScreenRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn recording_command(
+    dir: &std::path::Path,
+    name: &str,
+) -> Result<(Command, PathBuf, bool), String> {
+    let path = dir.join(format!("{}.mov", name));
+    let mut cmd = Command::new("screencapture");
+    cmd.arg("-v").arg(&path);
+    Ok((cmd, path, false))
+}
+
+#[cfg(target_os = "linux")]
+fn recording_command(
+    dir: &std::path::Path,
+    name: &str,
+) -> Result<(Command, PathBuf, bool), String> {
+    let path = dir.join(format!("{}.mp4", name));
+
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut cmd = Command::new("wf-recorder");
+        cmd.arg("-f").arg(&path);
+        return Ok((cmd, path, false));
+    }
+
+    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-f", "x11grab", "-i", &display]).arg(&path);
+    Ok((cmd, path, true))
+}
+
+#[cfg(target_os = "windows")]
+fn recording_command(
+    dir: &std::path::Path,
+    name: &str,
+) -> Result<(Command, PathBuf, bool), String> {
+    let path = dir.join(format!("{}.mp4", name));
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-f", "gdigrab", "-i", "desktop"])
+        .arg(&path);
+    Ok((cmd, path, true))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn recording_command(
+    _dir: &std::path::Path,
+    _name: &str,
+) -> Result<(Command, PathBuf, bool), String> {
+    Err("Screen recording is not supported on this platform".to_string())
+}
+
+#[cfg(unix)]
+fn interrupt(child: &mut Child) -> Result<(), String> {
+    // SIGINT lets screencapture/wf-recorder flush the file before exiting
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .map_err(|e| format!("Failed to stop screen recording: {}", e))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn interrupt(child: &mut Child) -> Result<(), String> {
+    child
+        .kill()
+        .map_err(|e| format!("Failed to stop screen recording: {}", e))
+}
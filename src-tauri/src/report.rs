@@ -0,0 +1,183 @@
+use base64::Engine;
+use chrono::TimeZone;
+use serde::{Deserialize, Serialize};
+
+use crate::code_lang::looks_like_code;
+use crate::models::ClipboardItemModel;
+
+const THUMBNAIL_SIZE: u32 = 240;
+
+/**
+ * Output format of export_report
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/**
+ * Render items as a document grouped by day, in the order given
+ */
+pub fn render(items: &[ClipboardItemModel], format: ReportFormat) -> String {
+    let mut out = String::new();
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M");
+
+    match format {
+        ReportFormat::Markdown => {
+            out.push_str(&format!("# Copyclip history\n\n_Exported {}_\n", generated));
+        }
+        ReportFormat::Html => {
+            out.push_str(concat!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+                "<title>Copyclip history</title>\n<style>\n",
+                "body { font-family: sans-serif; max-width: 48rem; margin: 2rem auto; }\n",
+                "pre { background: #f4f4f4; padding: .75rem; overflow-x: auto; }\n",
+                "blockquote { border-left: 3px solid #ccc; margin: 0; padding-left: .75rem; white-space: pre-wrap; }\n",
+                ".meta { color: #777; font-size: .85rem; }\n",
+                "</style>\n</head>\n<body>\n<h1>Copyclip history</h1>\n"
+            ));
+            out.push_str(&format!("<p class=\"meta\">Exported {}</p>\n", generated));
+        }
+    }
+
+    let mut current_day = String::new();
+    for item in items {
+        let time = local_time(item.timestamp);
+        let day = time.format("%A, %Y-%m-%d").to_string();
+        if day != current_day {
+            match format {
+                ReportFormat::Markdown => out.push_str(&format!("\n## {}\n", day)),
+                ReportFormat::Html => out.push_str(&format!("<h2>{}</h2>\n", escape_html(&day))),
+            }
+            current_day = day;
+        }
+
+        let meta = format!("{} · {}", time.format("%H:%M"), item.item_type);
+        match format {
+            ReportFormat::Markdown => render_markdown(&mut out, item, &meta),
+            ReportFormat::Html => render_html(&mut out, item, &meta),
+        }
+    }
+
+    if format == ReportFormat::Html {
+        out.push_str("</body>\n</html>\n");
+    }
+    out
+}
+
+fn render_markdown(out: &mut String, item: &ClipboardItemModel, meta: &str) {
+    out.push_str(&format!("\n**{}**", meta));
+    if let Some(url) = &item.source_url {
+        let title = item.source_title.as_deref().unwrap_or(url);
+        out.push_str(&format!(" — [{}]({})", title.replace(['[', ']'], ""), url));
+    }
+    out.push_str("\n\n");
+
+    if let Some(image) = &item.image_base64 {
+        match thumbnail(image) {
+            Some(data) => out.push_str(&format!("![image](data:image/png;base64,{})\n", data)),
+            None => out.push_str("_[image]_\n"),
+        }
+    } else if let Some(paths) = file_list(item) {
+        for path in paths {
+            out.push_str(&format!("- `{}`\n", path));
+        }
+    } else if item.language.is_some() || looks_like_code(&item.content) {
+        let fence = "`".repeat(longest_backtick_run(&item.content).max(2) + 1);
+        out.push_str(&format!(
+            "{}{}\n{}\n{}\n",
+            fence,
+            item.language.as_deref().unwrap_or_default(),
+            item.content.trim_end(),
+            fence
+        ));
+    } else {
+        for line in item.content.trim_end().lines() {
+            out.push_str(&format!("> {}\n", line));
+        }
+    }
+}
+
+fn render_html(out: &mut String, item: &ClipboardItemModel, meta: &str) {
+    out.push_str(&format!("<p class=\"meta\">{}", escape_html(meta)));
+    if let Some(url) = &item.source_url {
+        let title = item.source_title.as_deref().unwrap_or(url);
+        out.push_str(&format!(
+            " — <a href=\"{}\">{}</a>",
+            escape_html(url),
+            escape_html(title)
+        ));
+    }
+    out.push_str("</p>\n");
+
+    if let Some(image) = &item.image_base64 {
+        match thumbnail(image) {
+            Some(data) => out.push_str(&format!(
+                "<img alt=\"image\" src=\"data:image/png;base64,{}\">\n",
+                data
+            )),
+            None => out.push_str("<p><em>[image]</em></p>\n"),
+        }
+    } else if let Some(paths) = file_list(item) {
+        out.push_str("<ul>\n");
+        for path in paths {
+            out.push_str(&format!("<li><code>{}</code></li>\n", escape_html(&path)));
+        }
+        out.push_str("</ul>\n");
+    } else if item.language.is_some() || looks_like_code(&item.content) {
+        out.push_str(&format!(
+            "<pre><code>{}</code></pre>\n",
+            escape_html(item.content.trim_end())
+        ));
+    } else {
+        out.push_str(&format!(
+            "<blockquote>{}</blockquote>\n",
+            escape_html(item.content.trim_end())
+        ));
+    }
+}
+
+fn local_time(timestamp: i64) -> chrono::DateTime<chrono::Local> {
+    chrono::Local
+        .timestamp_millis_opt(timestamp)
+        .single()
+        .unwrap_or_else(chrono::Local::now)
+}
+
+fn file_list(item: &ClipboardItemModel) -> Option<Vec<String>> {
+    item.file_paths
+        .as_deref()
+        .and_then(|paths| crate::files::parse_paths(paths).ok())
+}
+
+/**
+ * PNG thumbnail (base64) of a base64 image, None if it cannot be decoded
+ */
+fn thumbnail(image_base64: &str) -> Option<String> {
+    let data = image_base64
+        .split_once("base64,")
+        .map(|(_, data)| data)
+        .unwrap_or(image_base64);
+    let engine = base64::engine::general_purpose::STANDARD;
+
+    let image = image::load_from_memory(&engine.decode(data.trim()).ok()?).ok()?;
+    let mut png = Vec::new();
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(engine.encode(png))
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
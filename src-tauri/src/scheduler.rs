@@ -0,0 +1,273 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::db::DatabaseService;
+use crate::shutdown::Shutdown;
+
+/**
+ * Settings key prefix for a job's persisted status (pause flag and last run)
+ */
+pub const STATUS_SETTING_PREFIX: &str = "job_status_";
+
+type JobFn = dyn Fn(&AppHandle, &DatabaseService) -> Result<(), String> + Send + Sync;
+
+/**
+ * Job state returned to the frontend; runtime fields are refreshed on every read
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobStatus {
+    pub name: String,
+    pub interval_secs: u64,
+    pub paused: bool,
+    pub running: bool,
+    pub last_run: Option<i64>, // ms timestamp
+    pub last_duration_ms: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_run: Option<i64>, // ms timestamp, None while paused
+}
+
+struct Job {
+    interval: Duration,
+    jitter: Duration,
+    status: Mutex<JobStatus>,
+    trigger: Notify,
+    run: Box<JobFn>,
+}
+
+/**
+ * Runs recurring background work on the async runtime
+ * Each job sleeps `interval` plus a random share of `jitter` between runs
+ */
+pub struct Scheduler {
+    jobs: Mutex<BTreeMap<String, Arc<Job>>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /**
+     * Register a job and start its loop; a paused state saved earlier is restored
+     */
+    pub fn register<F>(
+        &self,
+        app: &AppHandle,
+        name: &str,
+        interval: Duration,
+        jitter: Duration,
+        run: F,
+    ) where
+        F: Fn(&AppHandle, &DatabaseService) -> Result<(), String> + Send + Sync + 'static,
+    {
+        let saved = app
+            .try_state::<DatabaseService>()
+            .and_then(|db| load_status(&db, name))
+            .unwrap_or_default();
+
+        let job = Arc::new(Job {
+            interval,
+            jitter,
+            status: Mutex::new(JobStatus {
+                name: name.to_string(),
+                interval_secs: interval.as_secs(),
+                running: false,
+                next_run: None,
+                ..saved
+            }),
+            trigger: Notify::new(),
+            run: Box::new(run),
+        });
+
+        if self
+            .jobs
+            .lock()
+            .insert(name.to_string(), job.clone())
+            .is_some()
+        {
+            log::warn!("Job '{}' registered twice, replacing it", name);
+        }
+
+        let app = app.clone();
+        let token = app.state::<Shutdown>().token();
+        tauri::async_runtime::spawn(async move {
+            while !token.is_cancelled() {
+                let delay = job.interval + random_jitter(job.jitter);
+                job.status.lock().next_run = Some(now_ms() + millis(delay));
+
+                let forced = tokio::select! {
+                    _ = tokio::time::sleep(delay) => false,
+                    _ = job.trigger.notified() => true,
+                    _ = token.cancelled() => break,
+                };
+                if job.status.lock().paused && !forced {
+                    continue;
+                }
+
+                let job = job.clone();
+                let app = app.clone();
+                if let Err(e) =
+                    tauri::async_runtime::spawn_blocking(move || job.execute(&app)).await
+                {
+                    log::error!("Job task failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /**
+     * Status of every registered job, sorted by name
+     */
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.jobs
+            .lock()
+            .values()
+            .map(|job| {
+                let mut status = job.status.lock().clone();
+                if status.paused {
+                    status.next_run = None;
+                }
+                status
+            })
+            .collect()
+    }
+
+    /**
+     * Pause or resume a job's schedule; run_now still works while paused
+     */
+    pub fn set_paused(
+        &self,
+        db: &DatabaseService,
+        name: &str,
+        paused: bool,
+    ) -> Result<JobStatus, String> {
+        let job = self.job(name)?;
+        let mut status = {
+            let mut status = job.status.lock();
+            status.paused = paused;
+            status.clone()
+        };
+        save_status(db, &status)?;
+        if paused {
+            status.next_run = None;
+        }
+        log::info!(
+            "Job '{}' {}",
+            name,
+            if paused { "paused" } else { "resumed" }
+        );
+        Ok(status)
+    }
+
+    /**
+     * Wake a job to run immediately; runs queued during a run happen right after it
+     */
+    pub fn run_now(&self, name: &str) -> Result<(), String> {
+        self.job(name)?.trigger.notify_one();
+        Ok(())
+    }
+
+    /**
+     * Whether any job is in the middle of a run
+     */
+    pub fn is_busy(&self) -> bool {
+        self.jobs
+            .lock()
+            .values()
+            .any(|job| job.status.lock().running)
+    }
+
+    fn job(&self, name: &str) -> Result<Arc<Job>, String> {
+        self.jobs
+            .lock()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown job '{}'", name))
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Job {
+    /**
+     * Run once on the current (blocking) thread and persist the outcome
+     */
+    fn execute(&self, app: &AppHandle) {
+        let Some(db) = app.try_state::<DatabaseService>() else {
+            log::warn!("Skipping job run, database not initialized");
+            return;
+        };
+
+        let name = {
+            let mut status = self.status.lock();
+            status.running = true;
+            status.name.clone()
+        };
+        let started_at = now_ms();
+        let started = Instant::now();
+        let result = (self.run)(app, &db);
+
+        let status = {
+            let mut status = self.status.lock();
+            status.running = false;
+            status.last_run = Some(started_at);
+            status.last_duration_ms = Some(millis(started.elapsed()));
+            status.last_error = result.err();
+            status.clone()
+        };
+        match &status.last_error {
+            Some(e) => log::error!("Job '{}' failed: {}", name, e),
+            None => log::debug!("Job '{}' finished", name),
+        }
+        if let Err(e) = save_status(&db, &status) {
+            log::error!("Failed to store status of job '{}': {}", name, e);
+        }
+    }
+}
+
+fn load_status(db: &DatabaseService, name: &str) -> Option<JobStatus> {
+    let key = format!("{}{}", STATUS_SETTING_PREFIX, name);
+    db.get_setting(&key)
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+}
+
+fn save_status(db: &DatabaseService, status: &JobStatus) -> Result<(), String> {
+    let key = format!("{}{}", STATUS_SETTING_PREFIX, status.name);
+    let value = serde_json::to_string(status).map_err(|e| e.to_string())?;
+    db.set_setting(&key, &value)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Random delay below `jitter` so jobs with equal intervals don't run in lockstep
+ */
+fn random_jitter(jitter: Duration) -> Duration {
+    let max = jitter.as_millis() as u64;
+    if max == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(uuid::Uuid::new_v4().as_u128() as u64 % max)
+}
+
+fn millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
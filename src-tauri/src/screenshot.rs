@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "linux")]
+use crate::capabilities::which;
+use crate::capabilities::{self, Feature};
+
+/**
+ * What part of the screen to capture
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotMode {
+    Full,
+    Window,
+    Region,
+}
+
+/**
+ * Capture a screenshot using the platform's native tooling
+ * Returns PNG bytes; region/window modes use the OS interactive selection
+ */
+pub fn capture(mode: ScreenshotMode) -> Result<Vec<u8>, String> {
+    capabilities::require(Feature::Screenshot)?;
+    let path = std::env::temp_dir().join(format!("copyclip-{}.png", uuid::Uuid::new_v4()));
+
+    let result = run_capture(mode, &path)
+        .and_then(|_| std::fs::read(&path).map_err(|e| format!("Screenshot was not saved: {}", e)));
+
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+/**
+ * Capture the focused window without any interaction (falls back to the full screen)
+ * Used for the context screenshots stored alongside copied items
+ */
+pub fn capture_active_window() -> Result<Vec<u8>, String> {
+    capabilities::require(Feature::ActiveWindowScreenshot)?;
+    let path = std::env::temp_dir().join(format!("copyclip-{}.png", uuid::Uuid::new_v4()));
+
+    let result = run_active_window_capture(&path)
+        .and_then(|_| std::fs::read(&path).map_err(|e| format!("Screenshot was not saved: {}", e)));
+
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+#[cfg(target_os = "macos")]
+fn run_capture(mode: ScreenshotMode, path: &Path) -> Result<(), String> {
+    let mut cmd = Command::new("screencapture");
+    cmd.arg("-x");
+    match mode {
+        ScreenshotMode::Full => {}
+        ScreenshotMode::Window => {
+            cmd.arg("-iW");
+        }
+        ScreenshotMode::Region => {
+            cmd.arg("-i");
+        }
+    }
+    run(cmd.arg(path))
+}
+
+#[cfg(target_os = "linux")]
+fn run_capture(mode: ScreenshotMode, path: &Path) -> Result<(), String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    if wayland && which("grim") {
+        let mut cmd = Command::new("grim");
+        if mode != ScreenshotMode::Full {
+            // grim has no window picker, let slurp select the area
+            let geometry = Command::new("slurp")
+                .output()
+                .map_err(|e| format!("Failed to run slurp: {}", e))?;
+            if !geometry.status.success() {
+                return Err("Screenshot selection cancelled".to_string());
+            }
+            cmd.arg("-g")
+                .arg(String::from_utf8_lossy(&geometry.stdout).trim());
+        }
+        return run(cmd.arg(path));
+    }
+
+    if which("gnome-screenshot") {
+        let mut cmd = Command::new("gnome-screenshot");
+        match mode {
+            ScreenshotMode::Full => {}
+            ScreenshotMode::Window => {
+                cmd.arg("-w");
+            }
+            ScreenshotMode::Region => {
+                cmd.arg("-a");
+            }
+        }
+        return run(cmd.arg("-f").arg(path));
+    }
+
+    if which("import") {
+        // ImageMagick: click a window or drag a region, or grab the root window
+        let mut cmd = Command::new("import");
+        if mode == ScreenshotMode::Full {
+            cmd.args(["-window", "root"]);
+        }
+        return run(cmd.arg(path));
+    }
+
+    Err(
+        "No screenshot tool found (install grim/slurp, gnome-screenshot or imagemagick)"
+            .to_string(),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn run_capture(mode: ScreenshotMode, path: &Path) -> Result<(), String> {
+    if mode != ScreenshotMode::Full {
+        return Err("Window and region screenshots are not supported on Windows yet".to_string());
+    }
+
+    let script = format!(
+        r#"Add-Type -AssemblyName System.Windows.Forms,System.Drawing;
+$b = [System.Windows.Forms.SystemInformation]::VirtualScreen;
+$bmp = New-Object System.Drawing.Bitmap $b.Width, $b.Height;
+$g = [System.Drawing.Graphics]::FromImage($bmp);
+$g.CopyFromScreen($b.Left, $b.Top, 0, 0, $bmp.Size);
+$bmp.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png);"#,
+        path.display()
+    );
+
+    let mut cmd = Command::new("powershell");
+    run(cmd.args(["-NoProfile", "-Command", &script]))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn run_capture(_mode: ScreenshotMode, _path: &Path) -> Result<(), String> {
+    Err("Screenshots are not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn run_active_window_capture(path: &Path) -> Result<(), String> {
+    // Bounds of the frontmost app's front window as "x, y, w, h"
+    let bounds = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to tell (first process whose frontmost is true) to get {position, size} of front window",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+
+    let mut cmd = Command::new("screencapture");
+    cmd.arg("-x");
+    if bounds.status.success() {
+        let rect = String::from_utf8_lossy(&bounds.stdout).replace(' ', "");
+        cmd.arg(format!("-R{}", rect.trim()));
+    }
+    run(cmd.arg(path))
+}
+
+#[cfg(target_os = "linux")]
+fn run_active_window_capture(path: &Path) -> Result<(), String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        // Wayland has no portable way to address the focused window
+        return run_capture(ScreenshotMode::Full, path);
+    }
+
+    if which("xdotool") && which("import") {
+        let window = Command::new("xdotool")
+            .arg("getactivewindow")
+            .output()
+            .map_err(|e| format!("Failed to run xdotool: {}", e))?;
+        if window.status.success() {
+            let id = String::from_utf8_lossy(&window.stdout).trim().to_string();
+            return run(Command::new("import").args(["-window", &id]).arg(path));
+        }
+    }
+
+    if which("gnome-screenshot") {
+        return run(Command::new("gnome-screenshot")
+            .args(["-w", "-f"])
+            .arg(path));
+    }
+
+    run_capture(ScreenshotMode::Full, path)
+}
+
+#[cfg(target_os = "windows")]
+fn run_active_window_capture(path: &Path) -> Result<(), String> {
+    let script = format!(
+        r#"Add-Type -AssemblyName System.Drawing;
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public struct RECT {{ public int Left, Top, Right, Bottom; }}
+public static class Win {{
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr hWnd, out RECT rect);
+}}
+"@;
+$r = New-Object RECT;
+[Win]::GetWindowRect([Win]::GetForegroundWindow(), [ref]$r) | Out-Null;
+$bmp = New-Object System.Drawing.Bitmap ($r.Right - $r.Left), ($r.Bottom - $r.Top);
+$g = [System.Drawing.Graphics]::FromImage($bmp);
+$g.CopyFromScreen($r.Left, $r.Top, 0, 0, $bmp.Size);
+$bmp.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png);"#,
+        path.display()
+    );
+
+    let mut cmd = Command::new("powershell");
+    run(cmd.args(["-NoProfile", "-Command", &script]))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn run_active_window_capture(_path: &Path) -> Result<(), String> {
+    Err("Screenshots are not supported on this platform".to_string())
+}
+
+fn run(cmd: &mut Command) -> Result<(), String> {
+    let status = cmd.status().map_err(|e| {
+        format!(
+            "Failed to run {}: {}",
+            cmd.get_program().to_string_lossy(),
+            e
+        )
+    })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Screenshot tool exited with {}", status))
+    }
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::capabilities::{self, Capabilities};
+use crate::capture_health::{CaptureHealth, CaptureStatus};
+use crate::clipboard::ClipboardBackend;
+use crate::db::DatabaseService;
+use crate::history_lock::HistoryLock;
+use crate::shared_library;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn, // works, but something is degraded
+    Fail,
+    Skip, // couldn't run, e.g. the database never opened
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub remediation: Option<String>,
+    pub duration_ms: u64,
+}
+
+/**
+ * Result of run_self_test, meant to be pasted into bug reports
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub version: String,
+    pub os: String,
+    pub ran_at: String,
+    pub passed: bool, // no check failed
+    pub checks: Vec<SelfTestCheck>,
+}
+
+type Outcome = (CheckStatus, String, Option<String>);
+
+/**
+ * Exercise each subsystem without changing anything the user can see
+ */
+pub fn run(app: &AppHandle) -> SelfTestReport {
+    let mut checks = vec![
+        check("database", || database(app)),
+        check("data_directory", data_directory),
+        check("clipboard_read", || clipboard_read(app)),
+        check("clipboard_watcher", || clipboard_watcher(app)),
+        check("capture_health", || capture_health(app)),
+        check("history_lock", || history_lock(app)),
+        check("shared_libraries", || shared_libraries(app)),
+    ];
+
+    // One check per external tool dependency, probed fresh so new installs count
+    let probed = app.state::<Capabilities>().get(true);
+    for support in probed.features.iter().filter(|s| s.supported) {
+        checks.push(SelfTestCheck {
+            name: format!("tools:{}", support.feature.key()),
+            status: match support.available {
+                true => CheckStatus::Pass,
+                false => CheckStatus::Warn,
+            },
+            detail: match &support.backend {
+                Some(backend) => format!("Using {}", backend),
+                None => format!("Missing {}", support.missing.join(", ")),
+            },
+            remediation: support.remediation.clone(),
+            duration_ms: 0,
+        });
+    }
+
+    let passed = !checks.iter().any(|c| c.status == CheckStatus::Fail);
+    log::info!(
+        "Self-test finished: {} checks, {}",
+        checks.len(),
+        if passed { "passed" } else { "failed" }
+    );
+    SelfTestReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: match capabilities::display_server() {
+            Some(server) => format!("{} ({:?})", std::env::consts::OS, server),
+            None => std::env::consts::OS.to_string(),
+        },
+        ran_at: chrono::Utc::now().to_rfc3339(),
+        passed,
+        checks,
+    }
+}
+
+fn check(name: &str, f: impl FnOnce() -> Outcome) -> SelfTestCheck {
+    let started = Instant::now();
+    let (status, detail, remediation) = f();
+    if status == CheckStatus::Fail {
+        log::warn!("Self-test check '{}' failed: {}", name, detail);
+    }
+    SelfTestCheck {
+        name: name.to_string(),
+        status,
+        detail,
+        remediation,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn database(app: &AppHandle) -> Outcome {
+    let Some(db) = app.try_state::<DatabaseService>() else {
+        return (
+            CheckStatus::Fail,
+            "Database is not initialized".to_string(),
+            Some(
+                "Check the log for the startup error, the data directory may not be writable"
+                    .to_string(),
+            ),
+        );
+    };
+
+    match db.check_round_trip() {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                CheckStatus::Fail,
+                "Read back a different value than was written".to_string(),
+                None,
+            )
+        }
+        Err(e) => {
+            return (
+                CheckStatus::Fail,
+                format!("Write failed: {}", e),
+                Some("Make sure no other program holds a lock on copyclip.db".to_string()),
+            )
+        }
+    }
+
+    match db.check_integrity(false) {
+        Ok(problems) if problems.is_empty() => (
+            CheckStatus::Pass,
+            format!("{} is readable and writable", db.path().display()),
+            None,
+        ),
+        Ok(problems) => (
+            CheckStatus::Fail,
+            format!("Integrity check reported: {}", problems.join("; ")),
+            Some("Restore a backup of the database".to_string()),
+        ),
+        Err(e) => (
+            CheckStatus::Fail,
+            format!("Integrity check failed: {}", e),
+            None,
+        ),
+    }
+}
+
+fn data_directory() -> Outcome {
+    let dir = crate::app_data_dir();
+    let probe = dir.join(format!(".self-test-{}", uuid::Uuid::new_v4()));
+
+    let result = std::fs::write(&probe, b"copyclip").and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => (
+            CheckStatus::Pass,
+            format!("{} is writable", dir.display()),
+            None,
+        ),
+        Err(e) => (
+            CheckStatus::Fail,
+            format!("Cannot write to {}: {}", dir.display(), e),
+            Some("Fix the permissions of the data directory".to_string()),
+        ),
+    }
+}
+
+fn clipboard_read(app: &AppHandle) -> Outcome {
+    let Some(backend) = app.try_state::<ClipboardBackend>() else {
+        return (
+            CheckStatus::Skip,
+            "Clipboard is not initialized".to_string(),
+            None,
+        );
+    };
+
+    let clipboard = backend.provider();
+    match clipboard.read_text() {
+        Ok(text) => (
+            CheckStatus::Pass,
+            format!("Read {} characters of text", text.chars().count()),
+            None,
+        ),
+        // Images and files don't have a text flavor, that is not a failure
+        Err(_) if clipboard.read_files().is_ok() || clipboard.read_image_base64().is_ok() => (
+            CheckStatus::Pass,
+            "Clipboard holds files or an image".to_string(),
+            None,
+        ),
+        Err(e) => (
+            CheckStatus::Warn,
+            format!("Could not read the clipboard: {}", e),
+            Some("Copy some text and run the self-test again".to_string()),
+        ),
+    }
+}
+
+fn clipboard_watcher(app: &AppHandle) -> Outcome {
+    let Some(backend) = app.try_state::<ClipboardBackend>() else {
+        return (
+            CheckStatus::Skip,
+            "Clipboard is not initialized".to_string(),
+            None,
+        );
+    };
+
+    match backend.provider().is_watching() {
+        true => (CheckStatus::Pass, "Watcher is running".to_string(), None),
+        false => (
+            CheckStatus::Fail,
+            "Watcher is not running, new copies are not captured".to_string(),
+            Some("Reopen the main window or restart copyclip".to_string()),
+        ),
+    }
+}
+
+fn capture_health(app: &AppHandle) -> Outcome {
+    let health = app.state::<CaptureHealth>().snapshot();
+    match health.status {
+        CaptureStatus::Healthy => (
+            CheckStatus::Pass,
+            format!("{} watcher restarts this session", health.restarts),
+            None,
+        ),
+        CaptureStatus::Unknown => (
+            CheckStatus::Pass,
+            "Nothing captured yet this session".to_string(),
+            None,
+        ),
+        CaptureStatus::Stalled => (
+            CheckStatus::Fail,
+            "Clipboard changed but nothing was captured".to_string(),
+            Some("Check the capture filters, then restart copyclip".to_string()),
+        ),
+    }
+}
+
+fn history_lock(app: &AppHandle) -> Outcome {
+    match app.state::<HistoryLock>().is_locked() {
+        false => (CheckStatus::Pass, "History is unlocked".to_string(), None),
+        true => (
+            CheckStatus::Warn,
+            "History is locked, reads and pastes are refused".to_string(),
+            Some("Unlock history with your master password".to_string()),
+        ),
+    }
+}
+
+fn shared_libraries(app: &AppHandle) -> Outcome {
+    let Some(db) = app.try_state::<DatabaseService>() else {
+        return (
+            CheckStatus::Skip,
+            "Database is not initialized".to_string(),
+            None,
+        );
+    };
+
+    let libraries = match shared_library::list(&db) {
+        Ok(libraries) => libraries,
+        Err(e) => return (CheckStatus::Fail, e, None),
+    };
+    let detached: Vec<String> = libraries
+        .iter()
+        .filter(|library| !library.attached)
+        .map(|library| library.name.clone())
+        .collect();
+
+    match detached.is_empty() {
+        true => (
+            CheckStatus::Pass,
+            format!("{} attached", libraries.len()),
+            None,
+        ),
+        false => (
+            CheckStatus::Warn,
+            format!("Not attached: {}", detached.join(", ")),
+            Some("Check that the library files exist and are synced".to_string()),
+        ),
+    }
+}
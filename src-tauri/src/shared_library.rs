@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::db::DatabaseService;
+
+/**
+ * JSON list of SharedLibrary attached read-only on startup
+ */
+pub const LIBRARIES_SETTING: &str = "shared_libraries";
+
+/**
+ * Snippet database shared by a team, e.g. a copyclip.db synced through Dropbox
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedLibrary {
+    pub name: String,
+    pub path: String,
+}
+
+/**
+ * Configured library and whether it is currently attached
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedLibraryStatus {
+    pub name: String,
+    pub path: String,
+    pub attached: bool,
+    pub item_count: Option<i64>, // None while not attached
+}
+
+/**
+ * Load configured libraries; a malformed setting counts as none
+ */
+pub fn load(db: &DatabaseService) -> Vec<SharedLibrary> {
+    let Some(value) = db.get_setting(LIBRARIES_SETTING).ok().flatten() else {
+        return Vec::new();
+    };
+
+    serde_json::from_str(&value).unwrap_or_else(|e| {
+        log::error!("Ignoring invalid {} setting: {}", LIBRARIES_SETTING, e);
+        Vec::new()
+    })
+}
+
+fn store(db: &DatabaseService, libraries: &[SharedLibrary]) -> Result<(), String> {
+    let value = serde_json::to_string(libraries).map_err(|e| e.to_string())?;
+    db.set_setting(LIBRARIES_SETTING, &value)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/**
+ * Attach every configured library, skipping (and logging) the ones that can't be opened
+ * A synced file may simply not be there yet, so it stays configured
+ */
+pub fn attach_all(db: &DatabaseService) {
+    for library in load(db) {
+        match db.attach_library(&library.name, Path::new(&library.path)) {
+            Ok(()) => log::info!("Attached shared library '{}'", library.name),
+            Err(e) => log::warn!(
+                "Failed to attach shared library '{}' ({}): {}",
+                library.name,
+                library.path,
+                e
+            ),
+        }
+    }
+}
+
+/**
+ * Attach a library and remember it for the next start
+ */
+pub fn add(
+    db: &DatabaseService,
+    name: &str,
+    path: &str,
+) -> Result<Vec<SharedLibraryStatus>, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Shared library needs a name".to_string());
+    }
+
+    let mut libraries = load(db);
+    if libraries.iter().any(|library| library.name == name) {
+        return Err(format!("A shared library named '{}' already exists", name));
+    }
+
+    let path = Path::new(path.trim())
+        .canonicalize()
+        .map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    if path == db.path().canonicalize().unwrap_or_default() {
+        return Err("The history database can't be its own shared library".to_string());
+    }
+
+    db.attach_library(name, &path).map_err(|e| e.to_string())?;
+    libraries.push(SharedLibrary {
+        name: name.to_string(),
+        path: path.display().to_string(),
+    });
+    store(db, &libraries)?;
+    list(db)
+}
+
+/**
+ * Detach a library and forget it
+ */
+pub fn remove(db: &DatabaseService, name: &str) -> Result<Vec<SharedLibraryStatus>, String> {
+    db.detach_library(name).map_err(|e| e.to_string())?;
+    let mut libraries = load(db);
+    libraries.retain(|library| library.name != name);
+    store(db, &libraries)?;
+    list(db)
+}
+
+pub fn list(db: &DatabaseService) -> Result<Vec<SharedLibraryStatus>, String> {
+    let counts = db.library_item_counts().map_err(|e| e.to_string())?;
+    Ok(load(db)
+        .into_iter()
+        .map(|library| SharedLibraryStatus {
+            attached: counts.contains_key(&library.name),
+            item_count: counts.get(&library.name).copied(),
+            name: library.name,
+            path: library.path,
+        })
+        .collect())
+}
@@ -0,0 +1,163 @@
+use parking_lot::{Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::scheduler::Scheduler;
+
+/**
+ * How long exit waits for background work before giving up on it
+ */
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+struct TokenState {
+    cancelled: AtomicBool,
+    lock: Mutex<()>,
+    wake: Condvar,
+    notify: Notify,
+}
+
+/**
+ * Cancellation signal handed to background threads and tasks
+ */
+#[derive(Clone)]
+pub struct ShutdownToken(Arc<TokenState>);
+
+impl ShutdownToken {
+    fn new() -> Self {
+        Self(Arc::new(TokenState {
+            cancelled: AtomicBool::new(false),
+            lock: Mutex::new(()),
+            wake: Condvar::new(),
+            notify: Notify::new(),
+        }))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /**
+     * Sleep on the current thread, waking early on shutdown
+     * Returns false once shutdown has started, so loops can use `while token.sleep(..)`
+     */
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let mut guard = self.0.lock.lock();
+        self.0
+            .wake
+            .wait_while_for(&mut guard, |_| !self.is_cancelled(), duration);
+        !self.is_cancelled()
+    }
+
+    /**
+     * Resolves once shutdown has started, for select! in async tasks
+     */
+    pub async fn cancelled(&self) {
+        let notified = self.0.notify.notified();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+
+    fn cancel(&self) {
+        {
+            let _guard = self.0.lock.lock();
+            self.0.cancelled.store(true, Ordering::SeqCst);
+        }
+        self.0.wake.notify_all();
+        self.0.notify.notify_waiters();
+    }
+}
+
+/**
+ * Owns the background threads so exit can stop and join them deterministically
+ */
+pub struct Shutdown {
+    token: ShutdownToken,
+    threads: Mutex<Vec<(String, JoinHandle<()>)>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            token: ShutdownToken::new(),
+            threads: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn token(&self) -> ShutdownToken {
+        self.token.clone()
+    }
+
+    /**
+     * Spawn a background thread that exit waits for; it should return soon after cancellation
+     */
+    pub fn spawn<F>(&self, name: &str, run: F)
+    where
+        F: FnOnce(ShutdownToken) + Send + 'static,
+    {
+        if self.token.is_cancelled() {
+            log::warn!("Not starting '{}', shutting down", name);
+            return;
+        }
+
+        let token = self.token();
+        let spawned = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || run(token));
+
+        match spawned {
+            Ok(handle) => {
+                let mut threads = self.threads.lock();
+                threads.retain(|(_, handle)| !handle.is_finished());
+                threads.push((name.to_string(), handle));
+            }
+            Err(e) => log::error!("Failed to start '{}': {}", name, e),
+        }
+    }
+
+    /**
+     * Cancel background work, wait for it up to SHUTDOWN_TIMEOUT and flush logs
+     * Called once when the app exits
+     */
+    pub fn run(&self, app: &AppHandle) {
+        log::info!("Shutting down background work");
+        self.token.cancel();
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+        let threads = std::mem::take(&mut *self.threads.lock());
+        for (name, handle) in threads {
+            while !handle.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(JOIN_POLL_INTERVAL);
+            }
+            if !handle.is_finished() {
+                log::warn!("Background thread '{}' did not stop in time", name);
+            } else if handle.join().is_err() {
+                log::error!("Background thread '{}' panicked", name);
+            }
+        }
+
+        if let Some(scheduler) = app.try_state::<Scheduler>() {
+            while scheduler.is_busy() && Instant::now() < deadline {
+                std::thread::sleep(JOIN_POLL_INTERVAL);
+            }
+            if scheduler.is_busy() {
+                log::warn!("Background jobs did not finish in time");
+            }
+        }
+
+        log::info!("Shutdown complete");
+        log::logger().flush();
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
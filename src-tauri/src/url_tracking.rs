@@ -0,0 +1,163 @@
+use crate::db::DatabaseService;
+
+/**
+ * Strip tracking parameters from URLs in newly captured text ("true" to enable)
+ * The text as copied is kept in original_content
+ */
+pub const ENABLED_SETTING: &str = "url_tracking_strip_enabled";
+/**
+ * Comma-separated extra parameters to strip; a trailing * matches a prefix (e.g. "ref_*")
+ */
+pub const PARAMS_SETTING: &str = "url_tracking_params";
+/**
+ * Also put the cleaned text back on the system clipboard ("true" to enable)
+ */
+pub const REPLACE_CLIPBOARD_SETTING: &str = "url_tracking_replace_clipboard";
+
+/**
+ * Parameters stripped by default; entries ending in * are prefixes
+ */
+const DEFAULT_PARAMS: &[&str] = &[
+    "utm_*",
+    "fbclid",
+    "gclid",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "ttclid",
+    "li_fat_id",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+];
+
+/**
+ * Query parameter names treated as tracking
+ */
+pub struct TrackingParams {
+    names: Vec<String>,
+    prefixes: Vec<String>,
+}
+
+impl Default for TrackingParams {
+    fn default() -> Self {
+        Self::from_list(DEFAULT_PARAMS.iter().copied())
+    }
+}
+
+impl TrackingParams {
+    /**
+     * Defaults plus the user's extra parameters
+     */
+    pub fn from_settings(db: &DatabaseService) -> Self {
+        let extra = db
+            .get_setting(PARAMS_SETTING)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        Self::from_list(DEFAULT_PARAMS.iter().copied().chain(extra.split(',')))
+    }
+
+    fn from_list<'a>(entries: impl Iterator<Item = &'a str>) -> Self {
+        let mut params = Self {
+            names: Vec::new(),
+            prefixes: Vec::new(),
+        };
+        for entry in entries.map(str::trim).filter(|entry| !entry.is_empty()) {
+            let entry = entry.to_ascii_lowercase();
+            match entry.strip_suffix('*') {
+                Some(prefix) => params.prefixes.push(prefix.to_string()),
+                None => params.names.push(entry),
+            }
+        }
+        params
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        self.names.contains(&key) || self.prefixes.iter().any(|prefix| key.starts_with(prefix))
+    }
+}
+
+/**
+ * Whether the capture pipeline should clean URLs
+ */
+pub fn enabled(db: &DatabaseService) -> bool {
+    db.get_setting(ENABLED_SETTING).ok().flatten().as_deref() == Some("true")
+}
+
+pub fn replace_clipboard(db: &DatabaseService) -> bool {
+    db.get_setting(REPLACE_CLIPBOARD_SETTING)
+        .ok()
+        .flatten()
+        .as_deref()
+        == Some("true")
+}
+
+/**
+ * Remove tracking parameters from every http(s) URL in the text
+ */
+pub fn strip(text: &str, params: &TrackingParams) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = find_url(rest) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\''))
+            .unwrap_or(rest.len());
+        // Sentence punctuation after a URL is not part of it
+        let url = rest[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+        out.push_str(&strip_url(url, params));
+        rest = &rest[url.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn find_url(text: &str) -> Option<usize> {
+    let lower = text.to_ascii_lowercase();
+    [lower.find("https://"), lower.find("http://")]
+        .into_iter()
+        .flatten()
+        .min()
+}
+
+fn strip_url(url: &str, params: &TrackingParams) -> String {
+    let Some((base, rest)) = url.split_once('?') else {
+        return url.to_string();
+    };
+
+    let (query, fragment) = match rest.split_once('#') {
+        Some((query, fragment)) => (query, Some(fragment)),
+        None => (rest, None),
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| !params.matches(pair.split('=').next().unwrap_or_default()))
+        .collect();
+
+    let mut url = base.to_string();
+    if !kept.is_empty() {
+        url.push('?');
+        url.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
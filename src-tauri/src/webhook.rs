@@ -0,0 +1,302 @@
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::DatabaseService;
+use crate::models::ClipboardItemModel;
+use crate::shutdown::Shutdown;
+
+/**
+ * JSON WebhookConfig; unset or an empty url disables the webhook
+ */
+pub const WEBHOOK_SETTING: &str = "webhook";
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const REDACTED: &str = "[REDACTED]";
+
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_DEVICE: &str = "/dev/null";
+
+/**
+ * Outbound webhook posted for every saved item
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<String>, // HMAC-SHA256 signing key
+    #[serde(default = "default_fields")]
+    pub fields: Vec<String>, // ClipboardItemModel fields included in the payload
+    #[serde(default = "default_true")]
+    pub redact_secrets: bool,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_fields() -> Vec<String> {
+    ["id", "content", "item_type", "timestamp"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+pub fn load(db: &DatabaseService) -> Option<WebhookConfig> {
+    let value = db.get_setting(WEBHOOK_SETTING).ok().flatten()?;
+    match serde_json::from_str::<WebhookConfig>(&value) {
+        Ok(config) if !config.url.trim().is_empty() => Some(config),
+        Ok(_) => None,
+        Err(e) => {
+            log::error!("Ignoring invalid {} setting: {}", WEBHOOK_SETTING, e);
+            None
+        }
+    }
+}
+
+pub fn validate(config: &WebhookConfig) -> Result<(), String> {
+    let url = config.url.trim();
+    let is_http = url.starts_with("https://") || url.starts_with("http://");
+    if !url.is_empty() && !is_http {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+    if config.max_attempts == 0 {
+        return Err("Webhook max_attempts must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/**
+ * Deliver the item to the configured webhook in the background
+ * Failed attempts are retried with exponential backoff; the outcome is logged
+ */
+pub fn notify(app: &AppHandle, item: &ClipboardItemModel) {
+    let Some(db) = app.try_state::<DatabaseService>() else {
+        return;
+    };
+    let Some((config, body)) = prepare(&db, item) else {
+        return;
+    };
+
+    let item_id = item.id.clone();
+    let shutdown = app.state::<Shutdown>();
+    let app = app.clone();
+    shutdown.spawn("webhook-delivery", move |token| {
+        // Give up on retries when the app exits, the failure is still recorded
+        let (attempts, result) = deliver(&config, &body, |backoff| token.sleep(backoff));
+        if let Some(db) = app.try_state::<DatabaseService>() {
+            record(&db, &item_id, &config, attempts, &result);
+        }
+    });
+}
+
+/**
+ * Deliver the item on the calling thread, retrying like notify
+ * For the native messaging host, which has no background workers
+ */
+pub fn notify_blocking(db: &DatabaseService, item: &ClipboardItemModel) {
+    let Some((config, body)) = prepare(db, item) else {
+        return;
+    };
+    let (attempts, result) = deliver(&config, &body, |backoff| {
+        std::thread::sleep(backoff);
+        true
+    });
+    record(db, &item.id, &config, attempts, &result);
+}
+
+fn prepare(db: &DatabaseService, item: &ClipboardItemModel) -> Option<(WebhookConfig, String)> {
+    let config = load(db)?;
+    match payload(&config, item) {
+        Ok(body) => Some((config, body)),
+        Err(e) => {
+            log::error!("Failed to build webhook payload: {}", e);
+            None
+        }
+    }
+}
+
+/**
+ * POST with retries; `wait` sleeps between attempts and returns false to stop retrying
+ */
+fn deliver(
+    config: &WebhookConfig,
+    body: &str,
+    wait: impl Fn(Duration) -> bool,
+) -> (u32, Result<u16, String>) {
+    let mut attempts = 0;
+    let mut backoff = Duration::from_secs(1);
+    let result = loop {
+        attempts += 1;
+        match post(config, body) {
+            Ok(status) => break Ok(status),
+            Err(e) if attempts >= config.max_attempts => break Err(e),
+            Err(e) => {
+                log::warn!("Webhook attempt {} failed: {}", attempts, e);
+                if !wait(backoff) {
+                    break Err(e);
+                }
+                backoff *= 2;
+            }
+        }
+    };
+    (attempts, result)
+}
+
+fn record(
+    db: &DatabaseService,
+    item_id: &str,
+    config: &WebhookConfig,
+    attempts: u32,
+    result: &Result<u16, String>,
+) {
+    if let Err(e) = result {
+        log::error!("Webhook delivery for {} failed: {}", item_id, e);
+    }
+    if let Err(e) = db.record_webhook_delivery(item_id, &config.url, attempts, result) {
+        log::error!("Failed to record webhook delivery: {}", e);
+    }
+}
+
+/**
+ * JSON body with only the configured fields, secrets redacted
+ */
+fn payload(config: &WebhookConfig, item: &ClipboardItemModel) -> Result<String, String> {
+    let mut value = serde_json::to_value(item).map_err(|e| e.to_string())?;
+    let object = value.as_object_mut().ok_or("item is not an object")?;
+    object.retain(|key, _| config.fields.iter().any(|field| field == key));
+
+    if config.redact_secrets {
+        for key in ["content", "html", "rtf"] {
+            if let Some(serde_json::Value::String(text)) = object.get_mut(key) {
+                *text = redact(text);
+            }
+        }
+    }
+
+    serde_json::to_string(&value).map_err(|e| e.to_string())
+}
+
+/**
+ * Replace things that look like credentials: private keys, JWTs, API tokens
+ */
+pub fn redact(text: &str) -> String {
+    if text.contains("-----BEGIN") && text.contains("PRIVATE KEY-----") {
+        return REDACTED.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, word) in words(text) {
+        if looks_secret(word) {
+            output.push_str(&text[last..start]);
+            output.push_str(REDACTED);
+            last = start + word.len();
+        }
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '=' | ',' | ';'))
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+fn looks_secret(word: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "sk-",
+        "sk_live_",
+        "rk_live_",
+        "ghp_",
+        "gho_",
+        "ghs_",
+        "github_pat_",
+        "glpat-",
+        "xoxb-",
+        "xoxp-",
+        "AKIA",
+        "AIza",
+    ];
+    let token_chars = word
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !token_chars || word.len() < 16 {
+        return false;
+    }
+
+    let is_jwt = word.starts_with("eyJ") && word.split('.').count() == 3;
+    is_jwt || PREFIXES.iter().any(|prefix| word.starts_with(prefix))
+}
+
+/**
+ * Hex HMAC-SHA256 of the body
+ */
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/**
+ * POST the body with curl, returning the HTTP status on 2xx
+ */
+fn post(config: &WebhookConfig, body: &str) -> Result<u16, String> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "-o", NULL_DEVICE, "-w", "%{http_code}", "-X", "POST"])
+        .args(["--max-time", &REQUEST_TIMEOUT_SECS.to_string()])
+        .args(["-H", "Content-Type: application/json"])
+        .args(["-H", "User-Agent: copyclip"]);
+    if let Some(secret) = config.secret.as_deref().filter(|s| !s.is_empty()) {
+        cmd.arg("-H").arg(format!(
+            "X-Copyclip-Signature: sha256={}",
+            sign(secret, body)
+        ));
+    }
+
+    let mut child = cmd
+        .args(["--data-binary", "@-"])
+        .arg(config.url.trim())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.as_bytes())
+            .map_err(|e| format!("failed to send body: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let status: u16 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(0);
+    if (200..300).contains(&status) {
+        Ok(status)
+    } else {
+        Err(format!("HTTP {}", status))
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent,
+};
+
+use crate::db::DatabaseService;
+use crate::focus::FocusMemory;
+
+/**
+ * Where show_main_window places the window: "saved" (default) or "cursor"
+ */
+pub const OPEN_POSITION_SETTING: &str = "window_open_position";
+
+/**
+ * Main window size from tauri.conf.json, used when resetting
+ */
+const DEFAULT_SIZE: (f64, f64) = (500.0, 720.0);
+
+/**
+ * Saved geometry of a window, in physical pixels
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowStateModel {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub monitor: Option<String>,
+}
+
+fn setting_key(window: &WebviewWindow) -> String {
+    format!("window_state_{}", window.label())
+}
+
+/**
+ * Persist the window's current geometry and monitor in settings
+ */
+pub fn save(window: &WebviewWindow, db: &DatabaseService) -> Result<(), String> {
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+
+    let state = WindowStateModel {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        monitor,
+    };
+    let value = serde_json::to_string(&state).map_err(|e| e.to_string())?;
+
+    db.set_setting(&setting_key(window), &value)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/**
+ * Restore saved geometry if its monitor is still connected and it is on screen
+ */
+pub fn restore(window: &WebviewWindow, db: &DatabaseService) -> Result<bool, String> {
+    let Some(value) = db
+        .get_setting(&setting_key(window))
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(false);
+    };
+    let state: WindowStateModel = serde_json::from_str(&value).map_err(|e| e.to_string())?;
+
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let on_screen = monitors.iter().any(|monitor| {
+        let same_monitor = state.monitor.is_none() || monitor.name() == state.monitor.as_ref();
+        let origin = monitor.position();
+        let size = monitor.size();
+        same_monitor
+            && state.x >= origin.x
+            && state.y >= origin.y
+            && i64::from(state.x) < i64::from(origin.x) + i64::from(size.width)
+            && i64::from(state.y) < i64::from(origin.y) + i64::from(size.height)
+    });
+    if !on_screen {
+        log::info!(
+            "Saved {} window position is off screen, ignoring",
+            window.label()
+        );
+        return Ok(false);
+    }
+
+    window
+        .set_size(PhysicalSize::new(state.width, state.height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(state.x, state.y))
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/**
+ * Forget the saved geometry and re-center the window at its default size
+ */
+pub fn reset(window: &WebviewWindow, db: &DatabaseService) -> Result<(), String> {
+    db.delete_setting(&setting_key(window))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(LogicalSize::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1))
+        .map_err(|e| e.to_string())?;
+    window.center().map_err(|e| e.to_string())
+}
+
+/**
+ * Restore the window and keep its saved geometry up to date
+ * Geometry is saved when the window loses focus or closes, not on every move event
+ */
+pub fn track(window: &WebviewWindow) {
+    let app = window.app_handle().clone();
+    if let Some(db) = app.try_state::<DatabaseService>() {
+        if let Err(e) = restore(window, &db) {
+            log::error!("Failed to restore {} window state: {}", window.label(), e);
+        }
+    }
+
+    let tracked = window.clone();
+    window.on_window_event(move |event| {
+        if !matches!(
+            event,
+            WindowEvent::Focused(false) | WindowEvent::CloseRequested { .. }
+        ) {
+            return;
+        }
+        if let Some(db) = app.try_state::<DatabaseService>() {
+            if let Err(e) = save(&tracked, &db) {
+                log::error!("Failed to save {} window state: {}", tracked.label(), e);
+            }
+        }
+    });
+}
+
+/**
+ * Move the window so its top-left corner sits at the mouse cursor,
+ * kept inside the monitor under the cursor
+ */
+pub fn move_to_cursor(window: &WebviewWindow) -> Result<(), String> {
+    let cursor = window.cursor_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+
+    let mut x = cursor.x as i32;
+    let mut y = cursor.y as i32;
+    if let Some(monitor) = window
+        .monitor_from_point(cursor.x, cursor.y)
+        .map_err(|e| e.to_string())?
+    {
+        let origin = monitor.position();
+        let area = monitor.size();
+        let max_x = origin.x + area.width as i32 - size.width as i32;
+        let max_y = origin.y + area.height as i32 - size.height as i32;
+        x = x.min(max_x).max(origin.x);
+        y = y.min(max_y).max(origin.y);
+    }
+
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+/**
+ * Show, unminimize and focus the main window
+ * The focused app is remembered first so paste_item_and_restore_focus can go back to it,
+ * so every path bringing the window forward (launch, relaunch, dock, frontend) goes through here
+ */
+pub fn show_main(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    if !window.is_focused().unwrap_or(false) {
+        app.state::<FocusMemory>().remember();
+    }
+
+    if let Some(db) = app.try_state::<DatabaseService>() {
+        let open_position = db
+            .get_setting(OPEN_POSITION_SETTING)
+            .map_err(|e| e.to_string())?;
+        if open_position.as_deref() == Some("cursor") {
+            move_to_cursor(&window)?;
+        }
+    }
+
+    window.show().map_err(|e| e.to_string())?;
+    window.unminimize().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
@@ -10,10 +10,31 @@ import {
   onFilesUpdate,
   startListening,
   onClipboardUpdate,
+  hasHTML,
+  hasRTF,
+  hasText,
+  readHtml,
+  readRtf,
 } from "tauri-plugin-clipboard-api";
 import { databaseService } from "@/services/databaseService";
 import { ItemType } from "@/types/clipboard.types";
 
+/**
+ * Read the rich text flavors copied together with plain text
+ * Missing or unreadable flavors are left out, the text item is saved either way
+ */
+const readRichFlavors = async (): Promise<Pick<ItemType, "html" | "rtf">> => {
+  const [html, rtf] = await Promise.all([
+    hasHTML()
+      .then((has) => (has ? readHtml() : undefined))
+      .catch(() => undefined),
+    hasRTF()
+      .then((has) => (has ? readRtf() : undefined))
+      .catch(() => undefined),
+  ]);
+  return { html: html || undefined, rtf: rtf || undefined };
+};
+
 /**
  * Custom hook to monitor clipboard changes
  * Automatically adds clipboard updates to history
@@ -32,7 +53,7 @@ export const useClipboardMonitor = (enabled: boolean = true) => {
     try {
       console.info("[CLIPBOARD] Setting up clipboard listeners...");
       // Create text update listener
-      const unlistenText = await onTextUpdate((text: string) => {
+      const unlistenText = await onTextUpdate(async (text: string) => {
         if (text && text.trim()) {
           const item: ItemType = {
             content: text,
@@ -40,6 +61,7 @@ export const useClipboardMonitor = (enabled: boolean = true) => {
             isPinned: false,
             id: crypto.randomUUID(),
             timestamp: Date.now(),
+            ...(await readRichFlavors()),
           };
           addItem(item);
           // Save to database asynchronously
@@ -76,8 +98,9 @@ export const useClipboardMonitor = (enabled: boolean = true) => {
       });
 
       // Create HTML update listener
-      const unlistenHtml = await onHTMLUpdate((html: string) => {
-        if (html && html.trim()) {
+      // HTML copied with text is stored on the text item, only markup-only copies get their own item
+      const unlistenHtml = await onHTMLUpdate(async (html: string) => {
+        if (html && html.trim() && !(await hasText().catch(() => false))) {
           const item = {
             content: html,
             type: "html" as const,
@@ -116,9 +139,9 @@ export const useClipboardMonitor = (enabled: boolean = true) => {
         }
       });
 
-      // onRTFUpdate, onImageBinaryUpdate, onSomethingUpdate can be added similarly if needed
-      const unlistenRTF = await onRTFUpdate((rtf: string) => {
-        console.info("[CLIPBOARD] RTF update received, ignoring for now", rtf);
+      // RTF is read by the text listener and saved with the text item
+      const unlistenRTF = await onRTFUpdate(() => {
+        console.info("[CLIPBOARD] RTF update received, saved with the text item");
       });
 
       const unlistenImageBinary = await onImageBinaryUpdate((data: any) => {
@@ -13,8 +13,11 @@ export const databaseService = {
     try {
       const result = await invoke<boolean>("save_clipboard_item", {
         ...item,
+        itemType: item.type,
         imageBase64: item.imageBase64 || null,
         filePaths: item.filePaths ? JSON.stringify(item.filePaths) : null,
+        html: item.html || null,
+        rtf: item.rtf || null,
       });
       return result;
     } catch (error) {
@@ -102,7 +105,11 @@ export const databaseService = {
    */
   async clearAll(): Promise<boolean> {
     try {
-      return await invoke<boolean>("clear_clipboard_history");
+      // The caller has already asked the user, so skip the confirmation token step
+      const result = await invoke<{ status: string }>("clear_clipboard_history", {
+        confirm: true,
+      });
+      return result.status === "done";
     } catch (error) {
       console.error("Failed to clear history:", error);
       return false;
@@ -17,6 +17,9 @@ export interface ItemType {
   filePaths?: string[];
   /** Preview for images (base64) */
   imageBase64?: string;
+  /** Rich text flavors copied alongside the plain text */
+  html?: string;
+  rtf?: string;
 }
 
 /**